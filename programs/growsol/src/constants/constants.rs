@@ -7,7 +7,8 @@ pub const TOKEN_DECIMALS: u8 = 9;
 /// Raw multiplier = 10^decimals (used to convert token units -> raw units).
 pub const TOKEN_BASE: u128 = 1_000_000_000u128; // 10^9
 
-/// Stage token caps (in token units, not raw). Keep these here so you can change supply easily.
+/// Default stage token caps (in token units, not raw) set at `initialize`; the owner can edit the
+/// ladder afterwards with the admin stage instructions.
 pub const STAGE_1_TOKENS: u128 = 150_000_000u128;
 pub const STAGE_2_TOKENS: u128 = 200_000_000u128;
 pub const STAGE_3_TOKENS: u128 = 200_000_000u128;
@@ -15,21 +16,32 @@ pub const STAGE_4_TOKENS: u128 = 225_000_000u128;
 pub const STAGE_5_TOKENS: u128 = 225_000_000u128;
 /// Number of lamports per SOL.
 pub const LAMPORTS_PER_SOL: u128 = 1_000_000_000u128;
-pub const USER_ALLOC_SIZE: usize = 32 + 8 + 8 + 1;
+/// Bonding-curve prices are fixed-point micro-cents per whole token (1 cent = 10^6).
+pub const CURVE_PRICE_SCALE: u128 = 1_000_000u128;
+/// Maximum number of payout destinations the owner can allowlist for withdrawals.
+pub const MAX_PAYOUT_DESTINATIONS: usize = 8;
+/// Maximum number of revenue-split beneficiaries.
+pub const MAX_BENEFICIARIES: usize = 8;
+/// Maximum number of stages in the price ladder.
+pub const MAX_STAGES: usize = 10;
+/// Maximum number of volume bonus tiers.
+pub const MAX_BONUS_TIERS: usize = 4;
+/// Number of limit-price buckets in the auction book.
+pub const AUCTION_BUCKETS: usize = 64;
+/// Basis-point denominator (100%).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Current on-chain layout versions; raise together with an upgrade step in `migrate`.
+pub const PRESALE_STATE_VERSION: u8 = 1;
+pub const USER_ALLOC_VERSION: u8 = 1;
+/// Spare bytes at the end of each account so new fields can be added without a realloc.
+pub const PRESALE_RESERVED_BYTES: usize = 64;
+pub const USER_ALLOC_RESERVED_BYTES: usize = 32;
 
-pub const PRESALE_SIZE: usize = 32 // owner
-        + 1 + 1 + 1 // bumps
-        + 8 // usd_per_sol
-        + 8 + 8 + 1 // start, end, paused
-        + 1 // current_stage
-        + (8 * 5) // 5 prices
-        + (8 * 5) // 5 caps
-        + (8 * 5) // 5 sold
-        + 8; // total_allocated
-
-/// SEEDS
+// SEEDS
 
 pub const MINT_SEED: &[u8] = b"mint_auth";
 pub const PRESALE_STATE_SEED: &[u8] = b"presale_state";
 pub const TREASURY_SEED: &[u8] = b"treasury";
-pub const USER_ALLOC_SEED: &[u8] = b"user_alloc";
\ No newline at end of file
+pub const USER_ALLOC_SEED: &[u8] = b"user_alloc";
+pub const AUCTION_BOOK_SEED: &[u8] = b"auction_book";
+pub const DENYLIST_SEED: &[u8] = b"denylisted";
\ No newline at end of file
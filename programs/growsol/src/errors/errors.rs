@@ -29,5 +29,101 @@ pub enum PresaleError {
     UnauthorizedBuyer,
     #[msg("Insufficient funds in treasury")]
     InsufficientFunds,
+    #[msg("Recipient is not an allowlisted payout destination")]
+    DestinationNotAllowed,
+    #[msg("Payout destination already allowlisted")]
+    DestinationAlreadyAllowed,
+    #[msg("Payout destination allowlist is full")]
+    DestinationListFull,
+    #[msg("Too many beneficiaries")]
+    TooManyBeneficiaries,
+    #[msg("Beneficiary shares must be non-zero, unique and sum to 10000 bps")]
+    InvalidBeneficiaryShares,
+    #[msg("No beneficiaries configured")]
+    NoBeneficiaries,
+    #[msg("Beneficiary accounts do not match the configured split")]
+    BeneficiaryMismatch,
+    #[msg("Nothing to distribute")]
+    NothingToDistribute,
+    #[msg("Withdrawal would drop the treasury below its rent-exempt minimum")]
+    TreasuryRentExemption,
+    #[msg("Insufficient tokens in presale vault")]
+    InsufficientVaultBalance,
+    #[msg("Mint does not match the presale mint")]
+    InvalidMint,
+    #[msg("Stage caps, reserved supply and bonus pool exceed max supply")]
+    InvalidSupplyConfig,
+    #[msg("Minting would exceed max supply")]
+    MaxSupplyExceeded,
+    #[msg("Sale has not ended yet")]
+    SaleNotEnded,
+    #[msg("Mint authority already revoked")]
+    MintFinalized,
+    #[msg("Instruction not available in this distribution mode")]
+    WrongDistributionMode,
+    #[msg("Not enough deposited inventory in the vault")]
+    InsufficientInventory,
+    #[msg("Unsold inventory already finalized")]
+    UnsoldAlreadyFinalized,
+    #[msg("Sale is finalized or cancelled")]
+    SaleLocked,
+    #[msg("Sale has not been finalized")]
+    SaleNotFinalized,
+    #[msg("Sale is cancelled")]
+    SaleCancelled,
+    #[msg("Sale is not cancelled")]
+    SaleNotCancelled,
+    #[msg("Nothing to refund")]
+    NothingToRefund,
+    #[msg("Allocation already claimed; cannot refund")]
+    AlreadyClaimed,
+    #[msg("Allocation still has unclaimed tokens")]
+    AllocationNotFullyClaimed,
+    #[msg("Account is already on the current layout version")]
+    AlreadyMigrated,
+    #[msg("Stage start times must increase with the stage number")]
+    InvalidStageSchedule,
+    #[msg("Stage can no longer be edited")]
+    StageLocked,
+    #[msg("Stage cap cannot go below what the stage already sold")]
+    StageCapBelowSold,
+    #[msg("Stage price must be greater than zero")]
+    InvalidStagePrice,
+    #[msg("Stage ladder is full")]
+    TooManyStages,
+    #[msg("Bonding curve needs a positive start price, a non-decreasing end price and supply")]
+    InvalidCurveConfig,
+    #[msg("Only available when the sale prices on the stage ladder")]
+    LadderPricingOnly,
+    #[msg("Not available for this sale's pricing mode")]
+    WrongPricingMode,
+    #[msg("Sale has already started")]
+    SaleAlreadyStarted,
+    #[msg("Auction needs a positive floor price, tick and supply")]
+    InvalidAuctionConfig,
+    #[msg("Bid limit price is below the auction floor")]
+    BidBelowFloor,
+    #[msg("Bid limit price differs from this bidder's earlier bids")]
+    BidPriceMismatch,
+    #[msg("Auction has not been settled yet")]
+    AuctionNotSettled,
+    #[msg("Auction is already settled")]
+    AuctionAlreadySettled,
+    #[msg("Wallet must wait for its purchase cooldown to pass")]
+    PurchaseCooldown,
+    #[msg("Per-slot allocation ceiling reached, try again in a later slot")]
+    SlotCapExceeded,
+    #[msg("Wallet is denylisted")]
+    WalletDenylisted,
+    #[msg("Denylist entry account is required while any wallet is denylisted")]
+    DenylistEntryRequired,
+    #[msg("Allocation is frozen")]
+    AllocationFrozen,
+    #[msg("Invalid bonus config: rate above 100%, unordered tiers or pool below credited bonus")]
+    InvalidBonusConfig,
+    #[msg("Treasury is paid out through distribute_treasury while a revenue split is set")]
+    RevenueSplitActive,
+    #[msg("Beneficiary wallet cannot receive lamports")]
+    InvalidBeneficiaryWallet,
 }
 
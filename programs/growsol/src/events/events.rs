@@ -1,3 +1,4 @@
+use crate::state::presale_state::{ExpiredStagePolicy, SaleStatus, UnsoldAction, VolumeBasis};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -11,24 +12,31 @@ pub struct Initialized {
 pub struct TokensAllocated {
     pub buyer: Pubkey,
     pub allocated_raw: u64,
+    /// bonus credited on top of allocated_raw
+    pub bonus_raw: u64,
 }
 
 #[event]
 pub struct TokensClaimed {
     pub buyer: Pubkey,
     pub claimed_raw: u64,
+    /// Token-2022 transfer fee withheld; the buyer received claimed_raw - fee_raw
+    pub fee_raw: u64,
 }
 
 #[event]
 pub struct WithdrawnSol {
     pub owner: Pubkey,
+    pub recipient: Pubkey,
     pub amount: u64,
 }
 
 #[event]
 pub struct WithdrawnToken {
     pub owner: Pubkey,
+    pub recipient: Pubkey,
     pub amount_raw: u64,
+    pub fee_raw: u64,
 }
 
 #[event]
@@ -45,4 +53,238 @@ pub struct SalePaused {
 #[event]
 pub struct SaleResumed {
     pub owner: Pubkey,
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct PayoutDestinationAdded {
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct PayoutDestinationRemoved {
+    pub owner: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct BeneficiariesUpdated {
+    pub owner: Pubkey,
+    pub count: u8,
+}
+
+#[event]
+pub struct BeneficiaryPaid {
+    pub wallet: Pubkey,
+    pub amount: u64,
+    pub total_distributed: u64,
+}
+
+#[event]
+pub struct BeneficiaryPaymentDeferred {
+    pub wallet: Pubkey,
+    /// lamports now owed to the wallet, including what was held back before
+    pub owed: u64,
+}
+
+#[event]
+pub struct TreasuryDistributed {
+    pub caller: Pubkey,
+    pub amount: u64,
+    pub total_distributed: u64,
+}
+
+#[event]
+pub struct MintFinalized {
+    pub owner: Pubkey,
+    pub unclaimed_raw: u64,
+    pub reserved_raw: u64,
+    pub reserve_recipient: Pubkey,
+    pub total_minted: u64,
+}
+
+#[event]
+pub struct TokensDeposited {
+    pub owner: Pubkey,
+    /// net amount received by the vault after any Token-2022 transfer fee
+    pub amount_raw: u64,
+    pub fee_raw: u64,
+    pub total_deposited: u64,
+}
+
+#[event]
+pub struct UnsoldFinalized {
+    pub owner: Pubkey,
+    pub action: UnsoldAction,
+    pub amount_raw: u64,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct TokenMetadataUpdated {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+#[event]
+pub struct SaleFinalized {
+    pub owner: Pubkey,
+    pub total_allocated: u64,
+    pub total_raised_lamports: u64,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct SaleStatusChanged {
+    pub from: SaleStatus,
+    pub to: SaleStatus,
+}
+
+#[event]
+pub struct SaleCancelled {
+    pub owner: Pubkey,
+    pub total_raised_lamports: u64,
+}
+
+#[event]
+pub struct Refunded {
+    pub buyer: Pubkey,
+    pub lamports: u64,
+}
+
+/// Final record of an allocation whose account was closed after a full claim.
+#[event]
+pub struct AllocationClosed {
+    pub buyer: Pubkey,
+    pub allocated_raw: u64,
+    pub claimed_raw: u64,
+    pub paid_lamports: u64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct StageScheduleUpdated {
+    /// start times of stages 2..=stage_count (0 = sell-out only)
+    pub starts: Vec<i64>,
+    pub policy: ExpiredStagePolicy,
+}
+
+/// A stage's time ran out before it sold out.
+#[event]
+pub struct StageExpired {
+    pub stage: u8,
+    pub unsold_raw: u64,
+    pub rolled_forward: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum StageEdit {
+    Set,
+    Insert,
+    Remove,
+}
+
+/// Stage `stage` was edited; for `Remove`, price and cap are those of the removed stage.
+#[event]
+pub struct StageUpdated {
+    pub edit: StageEdit,
+    pub stage: u8,
+    pub price: u64,
+    pub cap: u64,
+    pub stage_count: u8,
+}
+
+/// Owner turned the sale into a uniform-price auction (prices in micro-cents per token).
+#[event]
+pub struct AuctionConfigured {
+    pub floor_price: u64,
+    pub tick: u64,
+    pub top_price: u64,
+    pub supply_raw: u64,
+}
+
+/// Owner turned the sale into a pro-rata fair launch at `price` micro-cents per token.
+#[event]
+pub struct ProRataConfigured {
+    pub price: u64,
+    pub supply_raw: u64,
+}
+
+#[event]
+pub struct BidCommitted {
+    pub bidder: Pubkey,
+    pub lamports: u64,
+    pub bucket: u8,
+    pub limit_price: u64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub clearing_price: u64,
+    pub marginal_bucket: u8,
+    pub allocated_raw: u64,
+    pub proceeds_lamports: u64,
+}
+
+/// A bid was turned into an allocation on claim; `refund_lamports` went back to the bidder.
+#[event]
+pub struct BidSettled {
+    pub bidder: Pubkey,
+    pub committed_lamports: u64,
+    pub allocated_raw: u64,
+    pub refund_lamports: u64,
+}
+
+/// Owner set the anti-sniping rule (`threshold_lamports` = 0 turns it off).
+#[event]
+pub struct ExtensionRuleUpdated {
+    pub threshold_lamports: u64,
+    pub window: u32,
+    pub step: u32,
+    pub max_extension: u32,
+}
+
+/// A large late purchase pushed the end of the sale back by `extension` seconds.
+#[event]
+pub struct SaleExtended {
+    pub presale_end: i64,
+    pub extension: u32,
+    pub total_extension: u32,
+}
+
+#[event]
+pub struct RateLimitsUpdated {
+    pub purchase_cooldown: u32,
+    pub slot_cap_raw: u64,
+}
+
+#[event]
+pub struct DenylistUpdated {
+    pub wallet: Pubkey,
+    pub denylisted: bool,
+    pub denylist_count: u32,
+}
+
+#[event]
+pub struct AllocationFreezeUpdated {
+    pub buyer: Pubkey,
+    pub frozen: bool,
+}
+
+#[event]
+pub struct BonusConfigUpdated {
+    pub early_bird_bps: u16,
+    pub early_bird_duration: u32,
+    pub tier_count: u8,
+    pub volume_basis: VolumeBasis,
+    pub pool_cap: u64,
+}
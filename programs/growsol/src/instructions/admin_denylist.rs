@@ -0,0 +1,129 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::denylist::Denylisted;
+use crate::state::presale_state::PresaleState;
+use crate::state::user_state::UserAllocation;
+use anchor_lang::prelude::*;
+
+/// Owner: block `wallet` from buying and claiming.
+pub fn admin_denylist_add(ctx: Context<AdminDenylistAdd>, wallet: Pubkey) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+
+    let entry = &mut ctx.accounts.denylist_entry;
+    entry.wallet = wallet;
+    entry.bump = ctx.bumps.denylist_entry;
+    state.denylist_count = state
+        .denylist_count
+        .checked_add(1)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(DenylistUpdated {
+        wallet,
+        denylisted: true,
+        denylist_count: state.denylist_count,
+    });
+    Ok(())
+}
+
+/// Owner: lift the block on `wallet`, returning the marker's rent to the owner.
+pub fn admin_denylist_remove(ctx: Context<AdminDenylistRemove>, wallet: Pubkey) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+
+    state.denylist_count = state
+        .denylist_count
+        .checked_sub(1)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(DenylistUpdated {
+        wallet,
+        denylisted: false,
+        denylist_count: state.denylist_count,
+    });
+    Ok(())
+}
+
+/// Owner: freeze or unfreeze one buyer's allocation. A frozen allocation keeps its tokens
+/// but cannot be claimed until it is unfrozen.
+pub fn admin_set_allocation_frozen(
+    ctx: Context<AdminSetAllocationFrozen>,
+    wallet: Pubkey,
+    frozen: bool,
+) -> Result<()> {
+    let state = ctx.accounts.presale_state.load()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+
+    ctx.accounts.user_allocation.frozen = frozen as u8;
+
+    emit!(AllocationFreezeUpdated {
+        buyer: wallet,
+        frozen
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AdminDenylistAdd<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [DENYLIST_SEED, wallet.as_ref()],
+        bump,
+        space = 8 + Denylisted::INIT_SPACE
+    )]
+    pub denylist_entry: Account<'info, Denylisted>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AdminDenylistRemove<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [DENYLIST_SEED, wallet.as_ref()],
+        bump = denylist_entry.bump
+    )]
+    pub denylist_entry: Account<'info, Denylisted>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AdminSetAllocationFrozen<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    #[account(
+        mut,
+        seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), wallet.as_ref()],
+        bump = user_allocation.bump
+    )]
+    pub user_allocation: Account<'info, UserAllocation>,
+}
@@ -0,0 +1,72 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{BonusTier, PresaleState, VolumeBasis, VolumeTier};
+use anchor_lang::prelude::*;
+
+/// Owner: configure bonus tokens credited on top of buy_tokens allocations. The early-bird
+/// rate applies for `early_bird_duration` seconds after presale_start; of the volume tiers
+/// (increasing thresholds in USD cents) the highest one reached applies on top. Bonuses
+/// are paid from a pool of `pool_cap` raw units and stop once it runs dry.
+pub fn admin_set_bonus(
+    ctx: Context<AdminSetBonus>,
+    early_bird_bps: u16,
+    early_bird_duration: u32,
+    volume_tiers: Vec<VolumeTier>,
+    volume_basis: VolumeBasis,
+    pool_cap: u64,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    require!(
+        volume_tiers.len() <= MAX_BONUS_TIERS
+            && early_bird_bps as u64 <= BPS_DENOMINATOR
+            && volume_tiers
+                .iter()
+                .all(|tier| tier.bps > 0 && tier.bps as u64 <= BPS_DENOMINATOR)
+            && volume_tiers
+                .windows(2)
+                .all(|pair| pair[0].min_usd_cents < pair[1].min_usd_cents),
+        PresaleError::InvalidBonusConfig
+    );
+    // bonuses already credited stay credited
+    require!(
+        pool_cap >= state.bonus_allocated,
+        PresaleError::InvalidBonusConfig
+    );
+
+    state.early_bird_bps = early_bird_bps;
+    state.early_bird_duration = early_bird_duration;
+    state.bonus_tiers = [BonusTier::default(); MAX_BONUS_TIERS];
+    for (slot, tier) in state.bonus_tiers.iter_mut().zip(&volume_tiers) {
+        slot.min_usd_cents = tier.min_usd_cents;
+        slot.bps = tier.bps;
+    }
+    state.bonus_tier_count = volume_tiers.len() as u8;
+    state.volume_basis = volume_basis as u8;
+    state.bonus_pool_cap = pool_cap;
+    state.check_supply()?;
+    state.check_inventory(Clock::get()?.unix_timestamp)?;
+
+    emit!(BonusConfigUpdated {
+        early_bird_bps,
+        early_bird_duration,
+        tier_count: state.bonus_tier_count,
+        volume_basis,
+        pool_cap,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminSetBonus<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
@@ -0,0 +1,45 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::PresaleState;
+use anchor_lang::prelude::*;
+
+/// Owner: configure the anti-sniping rule. Any purchase above `threshold_lamports` within
+/// `window` seconds of presale_end extends the sale by `step` seconds, up to `max_extension`
+/// seconds in total over the whole sale. A zero threshold turns the rule off.
+pub fn admin_set_extension_rule(
+    ctx: Context<AdminSetExtensionRule>,
+    threshold_lamports: u64,
+    window: u32,
+    step: u32,
+    max_extension: u32,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+
+    state.extension_threshold_lamports = threshold_lamports;
+    state.extension_window = window;
+    state.extension_step = step;
+    state.max_extension = max_extension;
+
+    emit!(ExtensionRuleUpdated {
+        threshold_lamports,
+        window,
+        step,
+        max_extension,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminSetExtensionRule<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
@@ -0,0 +1,38 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::PresaleState;
+use anchor_lang::prelude::*;
+
+/// Owner: set the seconds a wallet must wait between purchases and the raw units buy_tokens
+/// may allocate within one slot across all buyers (0 disables either limit).
+pub fn admin_set_rate_limits(
+    ctx: Context<AdminSetRateLimits>,
+    purchase_cooldown: u32,
+    slot_cap_raw: u64,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+
+    state.purchase_cooldown = purchase_cooldown;
+    state.slot_cap_raw = slot_cap_raw;
+
+    emit!(RateLimitsUpdated {
+        purchase_cooldown,
+        slot_cap_raw,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminSetRateLimits<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
@@ -0,0 +1,58 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{ExpiredStagePolicy, PresaleState, PricingMode};
+use anchor_lang::prelude::*;
+
+/// Owner: schedule start times for stages 2..=stage_count (0 = sell-out only) and choose
+/// what happens to the unsold capacity of a stage whose time runs out.
+pub fn admin_set_stage_schedule(
+    ctx: Context<AdminSetStageSchedule>,
+    starts: Vec<i64>,
+    policy: ExpiredStagePolicy,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    // a bonding curve is shaped by its parameters, not by stages
+    require!(
+        state.pricing_mode()? == PricingMode::Ladder,
+        PresaleError::LadderPricingOnly
+    );
+    require!(
+        starts.len() + 1 == state.stage_count as usize,
+        PresaleError::InvalidStageSchedule
+    );
+
+    // scheduled stages must open in order; unscheduled ones are skipped in the comparison
+    let mut last = 0;
+    for start in starts.iter().filter(|s| **s != 0) {
+        require!(*start > last, PresaleError::InvalidStageSchedule);
+        last = *start;
+    }
+
+    // stages whose time already passed are expired under the old policy first
+    let now = Clock::get()?.unix_timestamp;
+    state.advance_stage(now)?;
+
+    let count = state.stage_count as usize;
+    for (stage, start) in state.stages[1..count].iter_mut().zip(starts.iter()) {
+        stage.start = *start;
+    }
+    state.expired_stage_policy = policy as u8;
+
+    emit!(StageScheduleUpdated { starts, policy });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminSetStageSchedule<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
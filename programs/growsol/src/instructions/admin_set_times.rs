@@ -5,11 +5,13 @@ use crate::constants::*;
 use crate::errors::PresaleError;
 
 pub fn admin_set_times(ctx: Context<AdminSetTimes>, start_ts: i64, end_ts: i64) -> Result<()> {
-    let state = &mut ctx.accounts.presale_state;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
     require!(ctx.accounts.owner.key() == state.owner, PresaleError::Unauthorized);
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
 
     state.presale_start = start_ts;
     state.presale_end = end_ts;
+    state.refresh_status(Clock::get()?.unix_timestamp)?;
 
     emit!(PresaleTimesUpdated { start_ts, end_ts });
     Ok(())
@@ -20,6 +22,6 @@ pub struct AdminSetTimes<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.bump)]
-    pub presale_state: Account<'info, PresaleState>,
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
 }
\ No newline at end of file
@@ -0,0 +1,164 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{PresaleState, PricingMode, Stage};
+use anchor_lang::prelude::*;
+
+/// First stage the owner may restructure at `now`. Before `presale_start` the whole ladder
+/// is free (returns 1); once the sale has started only stages after the active one can be
+/// inserted, removed or repriced, and the active stage's cap can still move.
+fn first_free_stage(state: &mut PresaleState, now: i64) -> Result<u8> {
+    if now < state.presale_start {
+        return Ok(1);
+    }
+    // bring the ladder up to date so the active stage is `current_stage`
+    state.advance_stage(now)?;
+    Ok(state.current_stage + 1)
+}
+
+/// Owner: set price (cents) and cap (raw) of an existing stage.
+pub fn admin_set_stage(ctx: Context<AdminStage>, stage: u8, price: u64, cap: u64) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    // a bonding curve is shaped by its parameters, not by stages
+    require!(
+        state.pricing_mode()? == PricingMode::Ladder,
+        PresaleError::LadderPricingOnly
+    );
+    require!(price > 0, PresaleError::InvalidStagePrice);
+
+    let now = Clock::get()?.unix_timestamp;
+    let first_free = first_free_stage(&mut state, now)?;
+    let target = state.stage_mut(stage)?;
+    require!(stage + 1 >= first_free, PresaleError::StageLocked);
+    // buyers of the active stage keep the price they were quoted
+    require!(
+        stage >= first_free || price == target.price,
+        PresaleError::StageLocked
+    );
+    require!(cap >= target.sold, PresaleError::StageCapBelowSold);
+
+    target.price = price;
+    target.cap = cap;
+    state.check_supply()?;
+    state.check_inventory(now)?;
+    // a raised cap can reopen a sold-out sale, a lowered one can sell it out
+    state.refresh_status(now)?;
+
+    emit!(StageUpdated {
+        edit: StageEdit::Set,
+        stage,
+        price,
+        cap,
+        stage_count: state.stage_count,
+    });
+    Ok(())
+}
+
+/// Owner: insert a new stage at position `stage` (1-based), shifting later stages back.
+/// The new stage has no scheduled start.
+pub fn admin_insert_stage(
+    ctx: Context<AdminStage>,
+    stage: u8,
+    price: u64,
+    cap: u64,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    require!(
+        state.pricing_mode()? == PricingMode::Ladder,
+        PresaleError::LadderPricingOnly
+    );
+    require!(price > 0, PresaleError::InvalidStagePrice);
+
+    let count = state.stage_count as usize;
+    require!(count < MAX_STAGES, PresaleError::TooManyStages);
+    require!(
+        stage >= 1 && stage as usize <= count + 1,
+        PresaleError::InvalidStage
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        stage >= first_free_stage(&mut state, now)?,
+        PresaleError::StageLocked
+    );
+
+    let index = stage as usize - 1;
+    state.stages.copy_within(index..count, index + 1);
+    state.stages[index] = Stage {
+        price,
+        cap,
+        sold: 0,
+        start: 0,
+    };
+    state.stage_count += 1;
+    state.check_supply()?;
+    state.check_inventory(now)?;
+    state.refresh_status(now)?;
+
+    emit!(StageUpdated {
+        edit: StageEdit::Insert,
+        stage,
+        price,
+        cap,
+        stage_count: state.stage_count,
+    });
+    Ok(())
+}
+
+/// Owner: remove stage `stage` (1-based), shifting later stages forward. At least one stage
+/// always remains.
+pub fn admin_remove_stage(ctx: Context<AdminStage>, stage: u8) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    require!(
+        state.pricing_mode()? == PricingMode::Ladder,
+        PresaleError::LadderPricingOnly
+    );
+
+    let count = state.stage_count as usize;
+    require!(count > 1, PresaleError::InvalidStage);
+
+    let now = Clock::get()?.unix_timestamp;
+    let first_free = first_free_stage(&mut state, now)?;
+    let removed = *state.stage(stage)?;
+    require!(stage >= first_free, PresaleError::StageLocked);
+    require!(removed.sold == 0, PresaleError::StageCapBelowSold);
+
+    let index = stage as usize - 1;
+    state.stages.copy_within(index + 1..count, index);
+    state.stages[count - 1] = Stage::default();
+    state.stage_count -= 1;
+    state.refresh_status(now)?;
+
+    emit!(StageUpdated {
+        edit: StageEdit::Remove,
+        stage,
+        price: removed.price,
+        cap: removed.cap,
+        stage_count: state.stage_count,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminStage<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
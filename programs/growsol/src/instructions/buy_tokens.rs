@@ -1,29 +1,42 @@
 use crate::constants::*;
 use crate::errors::PresaleError;
 use crate::events::*;
-use crate::state::presale_state::PresaleState;
+use crate::state::denylist::Denylisted;
+use crate::state::presale_state::{
+    DistributionMode, PresaleState, PricingMode, SaleStatus, VolumeBasis,
+};
 use crate::state::user_state::UserAllocation;
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
     // Logic same as your original buy_tokens function
     let clock = Clock::get()?;
-    let state = &mut ctx.accounts.presale_state;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
 
-    require!(!state.paused, PresaleError::SalePaused);
-    require!(
-        clock.unix_timestamp >= state.presale_start,
-        PresaleError::SaleNotStarted
-    );
-    require!(
-        clock.unix_timestamp <= state.presale_end,
-        PresaleError::SaleEnded
-    );
+    require!(!state.is_paused(), PresaleError::SalePaused);
+    match state.refresh_status(clock.unix_timestamp)? {
+        SaleStatus::Live => {}
+        SaleStatus::Pending => return err!(PresaleError::SaleNotStarted),
+        SaleStatus::Ended => return err!(PresaleError::SaleEnded),
+        SaleStatus::Finalized | SaleStatus::Cancelled => return err!(PresaleError::SaleLocked),
+    }
+    // price rises on schedule even if the current stage has not sold out
+    state.advance_stage(clock.unix_timestamp)?;
     require!(state.usd_per_sol > 0, PresaleError::InvalidOraclePrice);
     require!(sol_amount > 0, PresaleError::ZeroPurchase);
+    state.check_inventory(clock.unix_timestamp)?;
+    Denylisted::check(ctx.accounts.denylist_entry.as_ref(), state.denylist_count)?;
+
+    // one wallet cannot fire purchases back to back
+    let last_purchase_at = ctx.accounts.user_allocation.last_purchase_at;
+    let cooldown_ends = last_purchase_at.saturating_add(state.purchase_cooldown as i64);
+    require!(
+        last_purchase_at == 0 || clock.unix_timestamp >= cooldown_ends,
+        PresaleError::PurchaseCooldown
+    );
 
     // Transfer SOL from buyer to treasury PDA (buyer is signer)
     system_program::transfer(
@@ -36,60 +49,135 @@ pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
         ),
         sol_amount,
     )?;
+    state.total_raised_lamports = state
+        .total_raised_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    // Convert lamports -> USD micro-cents
+    // usd_micro_cents = sol_amount (lamports) * usd_per_sol * 100 * 10^6 / 1_000_000_000
+    let usd_micro_cents = state.lamports_to_micro_cents(sol_amount)?;
+
+    let total_allocated_raw = match state.pricing_mode()? {
+        PricingMode::Ladder => allocate_ladder(&mut state, usd_micro_cents / CURVE_PRICE_SCALE)?,
+        PricingMode::LinearCurve => allocate_curve(&mut state, usd_micro_cents)?,
+        // commit sales go through commit_bid
+        PricingMode::Auction | PricingMode::ProRata => return err!(PresaleError::WrongPricingMode),
+    };
+
+    // mutable reference to user allocation PDA (init_if_needed ensures it exists)
+    let user_alloc = &mut ctx.accounts.user_allocation;
+
+    require!(total_allocated_raw > 0u128, PresaleError::ZeroTokens);
+
+    // and all buyers together cannot drain a stage within a single slot
+    state.record_slot_allocation(
+        clock.slot,
+        total_allocated_raw
+            .try_into()
+            .map_err(|_| PresaleError::MathOverflow)?,
+    )?;
 
-    // Convert lamports -> USD cents
-    // usd_cents = sol_amount (lamports) * usd_per_sol * 100 / 1_000_000_000
-    let sol_amount_u128 = sol_amount as u128;
-    let usd_per_sol_u128 = state.usd_per_sol as u128;
-    let mut usd_cents = sol_amount_u128
-        .checked_mul(usd_per_sol_u128)
-        .ok_or(PresaleError::MathOverflow)?
-        .checked_mul(100u128)
-        .ok_or(PresaleError::MathOverflow)?
-        .checked_div(1_000_000_000u128)
+    // update totals in state (convert to u64 safely)
+    state.total_allocated = state
+        .total_allocated
+        .checked_add(
+            total_allocated_raw
+                .try_into()
+                .map_err(|_| PresaleError::MathOverflow)?,
+        )
         .ok_or(PresaleError::MathOverflow)?;
 
-    // quick helper to get price and remaining raw cap for a stage
-    fn price_and_remaining(state: &PresaleState, stage: u8) -> Result<(u64, u128)> {
-        match stage {
-            1 => Ok((
-                state.stage_1_price,
-                (state.stage_1_cap - state.stage_1_sold) as u128,
-            )),
-            2 => Ok((
-                state.stage_2_price,
-                (state.stage_2_cap - state.stage_2_sold) as u128,
-            )),
-            3 => Ok((
-                state.stage_3_price,
-                (state.stage_3_cap - state.stage_3_sold) as u128,
-            )),
-            4 => Ok((
-                state.stage_4_price,
-                (state.stage_4_cap - state.stage_4_sold) as u128,
-            )),
-            5 => Ok((
-                state.stage_5_price,
-                (state.stage_5_cap - state.stage_5_sold) as u128,
-            )),
-            _ => Err(PresaleError::InvalidStage.into()),
-        }
+    // bonus on top, with volume tiers measured on this purchase or the buyer's running total
+    let usd_cents: u64 = (usd_micro_cents / CURVE_PRICE_SCALE)
+        .try_into()
+        .map_err(|_| PresaleError::MathOverflow)?;
+    let purchased_usd_cents = user_alloc
+        .purchased_usd_cents
+        .checked_add(usd_cents)
+        .ok_or(PresaleError::MathOverflow)?;
+    let volume_usd_cents = match state.volume_basis()? {
+        VolumeBasis::PerPurchase => usd_cents,
+        VolumeBasis::Cumulative => purchased_usd_cents,
+    };
+    let bonus_raw = state.allocate_bonus(
+        clock.unix_timestamp,
+        total_allocated_raw
+            .try_into()
+            .map_err(|_| PresaleError::MathOverflow)?,
+        volume_usd_cents,
+    )?;
+
+    // pre-funded sales can only allocate what is actually sitting in the vault
+    if state.distribution_mode()? == DistributionMode::PreFundedVault {
+        require!(
+            ctx.accounts.presale_token_account.amount >= state.unclaimed_raw()?,
+            PresaleError::InsufficientInventory
+        );
+    }
+
+    // a large purchase just before the end buys everyone else some time
+    state.extend_for_purchase(clock.unix_timestamp, sol_amount)?;
+
+    // a sell-out ends the sale right away
+    state.refresh_status(clock.unix_timestamp)?;
+
+    // update user allocation PDA: buyer and bump are fixed on first init and re-checked after
+    if user_alloc.buyer == Pubkey::default() {
+        user_alloc.buyer = ctx.accounts.buyer.key();
+        user_alloc.bump = ctx.bumps.user_allocation;
+        user_alloc.version = USER_ALLOC_VERSION;
     }
+    require!(
+        user_alloc.buyer == ctx.accounts.buyer.key(),
+        PresaleError::UnauthorizedBuyer
+    );
+    user_alloc.paid_lamports = user_alloc
+        .paid_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+    user_alloc.allocated_raw = user_alloc
+        .allocated_raw
+        .checked_add(
+            total_allocated_raw
+                .try_into()
+                .map_err(|_| PresaleError::MathOverflow)?,
+        )
+        .ok_or(PresaleError::MathOverflow)?;
+    user_alloc.bonus_raw = user_alloc
+        .bonus_raw
+        .checked_add(bonus_raw)
+        .ok_or(PresaleError::MathOverflow)?;
+    user_alloc.purchased_usd_cents = purchased_usd_cents;
+    user_alloc.last_purchase_at = clock.unix_timestamp;
+
+    emit!(TokensAllocated {
+        buyer: user_alloc.buyer,
+        allocated_raw: total_allocated_raw
+            .try_into()
+            .map_err(|_| PresaleError::MathOverflow)?,
+        bonus_raw,
+    });
 
+    Ok(())
+}
+
+/// Walk the price ladder from the current stage, spending `usd_cents` on whole tokens.
+/// Returns the raw units allocated.
+fn allocate_ladder(state: &mut PresaleState, mut usd_cents: u128) -> Result<u128> {
     let mut total_allocated_raw: u128 = 0;
     let mut stage = state.current_stage;
+    let last_stage = state.stage_count;
 
-    // mutable reference to user allocation PDA (init_if_needed ensures it exists)
-    let user_alloc = &mut ctx.accounts.user_allocation;
-
-    while usd_cents > 0 && stage <= 5 {
-        let (price_cents, remaining_raw) = price_and_remaining(state, stage)?;
+    while usd_cents > 0 && stage <= last_stage {
+        let current = state.stage_mut(stage)?;
+        let remaining_raw = current.remaining() as u128;
         if remaining_raw == 0 {
             stage = stage.checked_add(1).ok_or(PresaleError::InvalidStage)?;
             continue;
         }
 
-        let price_cents_u128 = price_cents as u128;
+        let price_cents_u128 = current.price as u128;
 
         // tokens (units) buyer can afford at this stage
         let tokens_units = usd_cents.checked_div(price_cents_u128).unwrap_or(0u128);
@@ -115,40 +203,12 @@ pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
             .checked_mul(price_cents_u128)
             .ok_or(PresaleError::MathOverflow)?;
 
-        // update stage sold counters (in raw units)
-        match stage {
-            1 => {
-                state.stage_1_sold = state
-                    .stage_1_sold
-                    .checked_add(to_allocate_raw as u64)
-                    .ok_or(PresaleError::MathOverflow)?
-            }
-            2 => {
-                state.stage_2_sold = state
-                    .stage_2_sold
-                    .checked_add(to_allocate_raw as u64)
-                    .ok_or(PresaleError::MathOverflow)?
-            }
-            3 => {
-                state.stage_3_sold = state
-                    .stage_3_sold
-                    .checked_add(to_allocate_raw as u64)
-                    .ok_or(PresaleError::MathOverflow)?
-            }
-            4 => {
-                state.stage_4_sold = state
-                    .stage_4_sold
-                    .checked_add(to_allocate_raw as u64)
-                    .ok_or(PresaleError::MathOverflow)?
-            }
-            5 => {
-                state.stage_5_sold = state
-                    .stage_5_sold
-                    .checked_add(to_allocate_raw as u64)
-                    .ok_or(PresaleError::MathOverflow)?
-            }
-            _ => return Err(PresaleError::InvalidStage.into()),
-        }
+        // update stage sold counter (in raw units)
+        current.sold = current
+            .sold
+            .checked_add(to_allocate_raw as u64)
+            .ok_or(PresaleError::MathOverflow)?;
+        let exhausted = current.remaining() == 0;
 
         total_allocated_raw = total_allocated_raw
             .checked_add(to_allocate_raw)
@@ -160,48 +220,36 @@ pub fn buy_tokens(ctx: Context<BuyTokens>, sol_amount: u64) -> Result<()> {
             .ok_or(PresaleError::MathOverflow)?;
 
         // advance stage if exhausted
-        let (_, rem_after) = price_and_remaining(state, stage)?;
-        if rem_after == 0 {
+        if exhausted {
             stage = stage.checked_add(1).ok_or(PresaleError::InvalidStage)?;
-        } else {
-            continue;
         }
     }
 
-    // clamp stage to max 5
-    state.current_stage = if stage > 5 { 5 } else { stage };
+    // clamp stage to the last one
+    state.current_stage = stage.min(last_stage);
 
-    require!(total_allocated_raw > 0u128, PresaleError::ZeroTokens);
+    Ok(total_allocated_raw)
+}
 
-    // update totals in state (convert to u64 safely)
-    state.total_allocated = state
-        .total_allocated
-        .checked_add(
-            total_allocated_raw
-                .try_into()
-                .map_err(|_| PresaleError::MathOverflow)?,
-        )
+/// Spend `usd_micro_cents` on the bonding curve. Returns the raw units allocated.
+fn allocate_curve(state: &mut PresaleState, usd_micro_cents: u128) -> Result<u128> {
+    let (tokens, _cost) = state.curve_quote(usd_micro_cents)?;
+    let allocated_raw = tokens
+        .checked_mul(TOKEN_BASE)
         .ok_or(PresaleError::MathOverflow)?;
 
-    // update user allocation PDA: set buyer & bump verification is on the account constraint
-    user_alloc.buyer = ctx.accounts.buyer.key();
-    user_alloc.allocated_raw = user_alloc
-        .allocated_raw
+    // the whole curve lives in stage 1
+    let stage = state.stage_mut(1)?;
+    stage.sold = stage
+        .sold
         .checked_add(
-            total_allocated_raw
+            allocated_raw
                 .try_into()
                 .map_err(|_| PresaleError::MathOverflow)?,
         )
         .ok_or(PresaleError::MathOverflow)?;
 
-    emit!(TokensAllocated {
-        buyer: user_alloc.buyer,
-        allocated_raw: total_allocated_raw
-            .try_into()
-            .map_err(|_| PresaleError::MathOverflow)?,
-    });
-
-    Ok(())
+    Ok(allocated_raw)
 }
 
 #[derive(Accounts)]
@@ -213,15 +261,15 @@ pub struct BuyTokens<'info> {
     #[account(
         mut,
         seeds = [PRESALE_STATE_SEED],
-        bump = presale_state.bump
+        bump = presale_state.load()?.bump
     )]
-    pub presale_state: Account<'info, PresaleState>,
+    pub presale_state: AccountLoader<'info, PresaleState>,
 
     /// treasury PDA (recipient of SOL)
     #[account(
         mut,
         seeds = [TREASURY_SEED],
-        bump = presale_state.treasury_bump
+        bump = presale_state.load()?.treasury_bump
     )]
     pub treasury: UncheckedAccount<'info>,
 
@@ -229,13 +277,21 @@ pub struct BuyTokens<'info> {
     #[account(
         mut,
         seeds = [MINT_SEED],
-        bump = presale_state.mint_bump
+        bump = presale_state.load()?.mint_bump
     )]
     pub mint_auth: UncheckedAccount<'info>,
 
-    /// existing mint
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    /// presale mint (pinned in presale_state)
+    #[account(address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// presale vault (holds inventory in pre-funded mode)
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// per-user allocation PDA (unique per presale_state & buyer)
     /// seeds = ["user_alloc", presale_state.key(), buyer.key()]
@@ -244,11 +300,65 @@ pub struct BuyTokens<'info> {
         payer = buyer,
         seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), buyer.key().as_ref()],
         bump,
-        space = 8 + USER_ALLOC_SIZE
+        space = 8 + UserAllocation::INIT_SPACE
     )]
     pub user_allocation: Account<'info, UserAllocation>,
 
-    pub token_program: Program<'info, Token>,
+    /// buyer's denylist marker address; may be left out while nobody is denylisted
+    #[account(seeds = [DENYLIST_SEED, buyer.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    const CAP: u64 = 1_000;
+    const PRICE: u64 = 3;
+
+    /// One ladder stage of `CAP` tokens at `PRICE` cents, `sold` of them gone.
+    fn ladder_state(sold: u64) -> PresaleState {
+        let mut state = PresaleState::zeroed();
+        state.stage_count = 1;
+        state.current_stage = 1;
+        state.stages[0].price = PRICE;
+        state.stages[0].cap = CAP * TOKEN_BASE as u64;
+        state.stages[0].sold = sold * TOKEN_BASE as u64;
+        state
+    }
+
+    /// The same stage as a flat curve.
+    fn flat_curve_state(sold: u64) -> PresaleState {
+        let mut state = ladder_state(sold);
+        state.pricing_mode = PricingMode::LinearCurve as u8;
+        state.curve_start_price = PRICE * CURVE_PRICE_SCALE as u64;
+        state.curve_end_price = state.curve_start_price;
+        state
+    }
+
+    #[test]
+    fn flat_curve_allocates_like_a_single_price_ladder_stage() {
+        for sold in [0, 500, CAP - 10, CAP - 1, CAP] {
+            let left = (CAP - sold) as u128 * PRICE as u128;
+            // just short of, exactly at and past the point the stage sells out
+            for cents in [1, 2, 3, 4, left.saturating_sub(1), left, left + 1, left + 3, 10 * left] {
+                let mut ladder = ladder_state(sold);
+                let allocated = allocate_ladder(&mut ladder, cents).unwrap();
+                let (tokens, _) = flat_curve_state(sold)
+                    .curve_quote(cents * CURVE_PRICE_SCALE)
+                    .unwrap();
+
+                assert_eq!(allocated, tokens * TOKEN_BASE, "sold {sold}, {cents} cents");
+                assert_eq!(
+                    ladder.stages[0].sold as u128,
+                    (sold as u128 + tokens) * TOKEN_BASE
+                );
+            }
+        }
+    }
+}
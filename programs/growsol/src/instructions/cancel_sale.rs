@@ -0,0 +1,38 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{PresaleState, SaleStatus};
+use anchor_lang::prelude::*;
+
+/// Owner: cancel the sale. Withdrawals and claims stop and buyers can `refund`.
+pub fn cancel_sale(ctx: Context<CancelSale>) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+
+    emit!(SaleStatusChanged {
+        from: state.status()?,
+        to: SaleStatus::Cancelled,
+    });
+    state.set_status(SaleStatus::Cancelled);
+
+    emit!(SaleCancelled {
+        owner: state.owner,
+        total_raised_lamports: state.total_raised_lamports,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelSale<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
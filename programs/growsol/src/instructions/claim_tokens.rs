@@ -1,61 +1,193 @@
 use crate::constants::*;
 use crate::errors::PresaleError;
 use crate::events::*;
-use crate::state::presale_state::PresaleState;
+use crate::instructions::withdraw_sol::treasury_withdrawable;
+use crate::state::auction_book::AuctionBook;
+use crate::state::denylist::Denylisted;
+use crate::state::presale_state::{PresaleState, SaleStatus};
 use crate::state::user_state::UserAllocation;
+use crate::utils::transfer_fee;
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, TokenAccount, Token, MintTo};
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Buyer claims everything allocated but not yet claimed. An auction bid is first turned
+/// into an allocation at the clearing price and its excess refunded; an outbid bidder's
+/// claim is just the refund. With `close_when_done` the allocation is closed back to the
+/// buyer once nothing is left to claim, which has to wait until the sale has ended.
+pub fn claim_tokens(ctx: Context<ClaimTokens>, close_when_done: bool) -> Result<()> {
+    require!(
+        ctx.accounts.user_allocation.buyer == ctx.accounts.buyer.key(),
+        PresaleError::UnauthorizedBuyer
+    );
+    // a closed and re-created allocation would start over on cooldown and cumulative volume
+    require!(
+        !close_when_done
+            || !ctx
+                .accounts
+                .presale_state
+                .load()?
+                .is_open(Clock::get()?.unix_timestamp)?,
+        PresaleError::SaleNotEnded
+    );
+    require!(
+        ctx.accounts.user_allocation.frozen == 0,
+        PresaleError::AllocationFrozen
+    );
+    Denylisted::check(
+        ctx.accounts.denylist_entry.as_ref(),
+        ctx.accounts.presale_state.load()?.denylist_count,
+    )?;
+    let refund_lamports = settle_bid(ctx.accounts)?;
 
-pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
-    let state = &ctx.accounts.presale_state;
     let user_alloc = &mut ctx.accounts.user_allocation;
-
+    let to_claim_raw_u64 = user_alloc
+        .entitled_raw()?
+        .checked_sub(user_alloc.claimed_raw)
+        .ok_or(PresaleError::MathOverflow)?;
     require!(
-        user_alloc.buyer == ctx.accounts.buyer.key(),
-        PresaleError::Unauthorized
+        to_claim_raw_u64 > 0 || refund_lamports > 0,
+        PresaleError::NothingToClaim
     );
 
-    let allocated = user_alloc.allocated_raw as u128;
-    let claimed = user_alloc.claimed_raw as u128;
-    require!(allocated > claimed, PresaleError::NothingToClaim);
+    let (bump, mint_bump, from_vault) = {
+        let mut state = ctx.accounts.presale_state.load_mut()?;
+        require!(
+            state.status()? != SaleStatus::Cancelled,
+            PresaleError::SaleCancelled
+        );
+        let from_vault = state.claims_from_vault()?;
+        if !from_vault {
+            state.record_mint(to_claim_raw_u64)?;
+        }
+        (state.bump, state.mint_bump, from_vault)
+    };
+
+    // Token-2022 transfer fees are withheld from vault transfers and reported in the event
+    let mut fee_raw = 0;
+    if to_claim_raw_u64 == 0 {
+        // nothing to deliver, only the refund
+    } else if from_vault {
+        // pre-funded sale, or mint authority revoked with unclaimed allocations pre-minted;
+        // presale_state signs, so its data must not be borrowed during the CPI
+        let presale_state_seeds = &[PRESALE_STATE_SEED, &[bump]];
+        let signer_seeds = &[&presale_state_seeds[..]];
+
+        fee_raw = transfer_fee(&ctx.accounts.mint, to_claim_raw_u64)?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.presale_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.presale_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            to_claim_raw_u64,
+            ctx.accounts.mint.decimals,
+        )?;
+    } else {
+        // mint_to using mint_auth PDA as signer
+        let mint_auth_seeds = &[MINT_SEED, &[mint_bump]];
+        let signer_seeds = &[&mint_auth_seeds[..]];
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.mint_auth.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            to_claim_raw_u64,
+        )?;
+    }
+
+    if to_claim_raw_u64 > 0 {
+        // mark claimed
+        user_alloc.claimed_raw = user_alloc
+            .claimed_raw
+            .checked_add(to_claim_raw_u64)
+            .ok_or(PresaleError::MathOverflow)?;
+        let mut state = ctx.accounts.presale_state.load_mut()?;
+        state.total_claimed = state
+            .total_claimed
+            .checked_add(to_claim_raw_u64)
+            .ok_or(PresaleError::MathOverflow)?;
+
+        emit!(TokensClaimed {
+            buyer: user_alloc.buyer,
+            claimed_raw: to_claim_raw_u64,
+            fee_raw,
+        });
+    }
+
+    if close_when_done && user_alloc.claimed_raw == user_alloc.entitled_raw()? {
+        emit!(AllocationClosed {
+            buyer: user_alloc.buyer,
+            allocated_raw: user_alloc.allocated_raw,
+            claimed_raw: user_alloc.claimed_raw,
+            paid_lamports: user_alloc.paid_lamports,
+        });
+        user_alloc.close(ctx.accounts.buyer.to_account_info())?;
+    }
 
-    let to_claim_raw_u128 = allocated
-        .checked_sub(claimed)
-        .ok_or(PresaleError::MathOverflow)?;
-    let to_claim_raw_u64: u64 = to_claim_raw_u128
-        .try_into()
-        .map_err(|_| PresaleError::MathOverflow)?;
-
-    // mint_to using mint_auth PDA as signer
-    let mint_auth_seeds = &[MINT_SEED.as_ref(), &[state.mint_bump]];
-    let signer_seeds = &[&mint_auth_seeds[..]];
-
-    token::mint_to(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.mint_auth.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        to_claim_raw_u64,
-    )?;
+    Ok(())
+}
 
-    // mark claimed
-    user_alloc.claimed_raw = user_alloc
-        .claimed_raw
-        .checked_add(to_claim_raw_u64)
+/// Turn an outstanding auction bid into an allocation and refund what the fill did not
+/// cost. Returns the refunded lamports (0 when there is no bid to settle).
+fn settle_bid(accounts: &mut ClaimTokens) -> Result<u64> {
+    let user_alloc = &mut accounts.user_allocation;
+    let committed = user_alloc.committed_lamports;
+    if committed == 0 {
+        return Ok(0);
+    }
+
+    let mut state = accounts.presale_state.load_mut()?;
+    let book = accounts
+        .auction_book
+        .as_ref()
+        .ok_or(ErrorCode::AccountNotEnoughKeys)?
+        .load()?;
+    let (allocated_raw, cost) = state.auction_fill(&book, committed, user_alloc.bid_bucket)?;
+    let refund_lamports = committed - cost;
+
+    if refund_lamports > 0 {
+        require!(
+            refund_lamports <= treasury_withdrawable(&accounts.treasury.to_account_info())?,
+            PresaleError::InsufficientFunds
+        );
+        // treasury is program-owned, so lamports are moved directly
+        accounts.treasury.sub_lamports(refund_lamports)?;
+        accounts.buyer.add_lamports(refund_lamports)?;
+        state.refunds_owed_lamports = state.refunds_owed_lamports.saturating_sub(refund_lamports);
+    }
+
+    user_alloc.committed_lamports = 0;
+    user_alloc.paid_lamports = user_alloc
+        .paid_lamports
+        .checked_sub(refund_lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+    user_alloc.allocated_raw = user_alloc
+        .allocated_raw
+        .checked_add(allocated_raw)
         .ok_or(PresaleError::MathOverflow)?;
 
-    emit!(TokensClaimed {
-        buyer: user_alloc.buyer,
-        claimed_raw: to_claim_raw_u64,
+    emit!(BidSettled {
+        bidder: user_alloc.buyer,
+        committed_lamports: committed,
+        allocated_raw,
+        refund_lamports,
     });
 
-    Ok(())
+    Ok(refund_lamports)
 }
 
 #[derive(Accounts)]
@@ -63,29 +195,56 @@ pub struct ClaimTokens<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
 
-    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.bump)]
-    pub presale_state: Account<'info, PresaleState>,
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// treasury PDA (source of auction refunds)
+    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.load()?.treasury_bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// auction book; only needed to settle an auction bid
+    #[account(seeds = [AUCTION_BOOK_SEED], bump = auction_book.load()?.bump)]
+    pub auction_book: Option<AccountLoader<'info, AuctionBook>>,
 
     /// mint auth PDA (must match presale_state.mint_bump)
-    #[account(mut, seeds = [MINT_SEED], bump = presale_state.mint_bump)]
+    #[account(mut, seeds = [MINT_SEED], bump = presale_state.load()?.mint_bump)]
     pub mint_auth: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    /// presale mint (pinned in presale_state)
+    #[account(mut, address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut, seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), buyer.key().as_ref()], bump)]
+    #[account(
+        mut,
+        seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), buyer.key().as_ref()],
+        bump = user_allocation.bump
+    )]
     pub user_allocation: Account<'info, UserAllocation>,
 
+    /// buyer's denylist marker address; may be left out while nobody is denylisted
+    #[account(seeds = [DENYLIST_SEED, buyer.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    /// presale vault (source of claims in pre-funded mode or once the mint authority is revoked)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
+
     /// buyer's ATA - will be created if missing
     #[account(
         init_if_needed,
         payer = buyer,
         associated_token::mint = mint,
         associated_token::authority = buyer,
+        associated_token::token_program = token_program,
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::PresaleState;
+use crate::state::user_state::UserAllocation;
+use anchor_lang::prelude::*;
+
+/// Buyer: close a fully claimed allocation after the sale, returning its rent to the buyer.
+/// The final totals are kept in the `AllocationClosed` event.
+pub fn close_allocation(ctx: Context<CloseAllocation>) -> Result<()> {
+    let user_alloc = &ctx.accounts.user_allocation;
+
+    require!(
+        user_alloc.buyer == ctx.accounts.buyer.key(),
+        PresaleError::UnauthorizedBuyer
+    );
+    // the allocation carries the buyer's cooldown and cumulative volume while the sale runs
+    require!(
+        !ctx.accounts
+            .presale_state
+            .load()?
+            .is_open(Clock::get()?.unix_timestamp)?,
+        PresaleError::SaleNotEnded
+    );
+    require!(
+        user_alloc.claimed_raw == user_alloc.entitled_raw()? && user_alloc.committed_lamports == 0,
+        PresaleError::AllocationNotFullyClaimed
+    );
+
+    emit!(AllocationClosed {
+        buyer: user_alloc.buyer,
+        allocated_raw: user_alloc.allocated_raw,
+        claimed_raw: user_alloc.claimed_raw,
+        paid_lamports: user_alloc.paid_lamports,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseAllocation<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// buyer's allocation, closed to the buyer
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), buyer.key().as_ref()],
+        bump = user_allocation.bump
+    )]
+    pub user_allocation: Account<'info, UserAllocation>,
+}
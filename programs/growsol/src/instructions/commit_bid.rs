@@ -0,0 +1,137 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::auction_book::AuctionBook;
+use crate::state::denylist::Denylisted;
+use crate::state::presale_state::{PresaleState, PricingMode, SaleStatus};
+use crate::state::user_state::UserAllocation;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// Bidder commits `sol_amount` lamports to an auction, willing to pay up to `limit_price`
+/// (micro-cents per whole token; `None` = any price), or to a pro-rata sale (no limit
+/// price). Repeat bids add to the same bucket.
+pub fn commit_bid(
+    ctx: Context<CommitBid>,
+    sol_amount: u64,
+    limit_price: Option<u64>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+
+    require!(!state.is_paused(), PresaleError::SalePaused);
+    match state.refresh_status(clock.unix_timestamp)? {
+        SaleStatus::Live => {}
+        SaleStatus::Pending => return err!(PresaleError::SaleNotStarted),
+        SaleStatus::Ended => return err!(PresaleError::SaleEnded),
+        SaleStatus::Finalized | SaleStatus::Cancelled => return err!(PresaleError::SaleLocked),
+    }
+    require!(sol_amount > 0, PresaleError::ZeroPurchase);
+    state.check_inventory(clock.unix_timestamp)?;
+    Denylisted::check(ctx.accounts.denylist_entry.as_ref(), state.denylist_count)?;
+
+    let bucket = match state.pricing_mode()? {
+        PricingMode::Auction => state.auction_bucket_for(limit_price)?,
+        // pro-rata commits all pay the one fixed price
+        PricingMode::ProRata if limit_price.is_none() => 0,
+        _ => return err!(PresaleError::WrongPricingMode),
+    };
+
+    let user_alloc = &mut ctx.accounts.user_allocation;
+    if user_alloc.buyer == Pubkey::default() {
+        user_alloc.buyer = ctx.accounts.bidder.key();
+        user_alloc.bump = ctx.bumps.user_allocation;
+        user_alloc.version = USER_ALLOC_VERSION;
+        user_alloc.bid_bucket = bucket;
+    }
+    require!(
+        user_alloc.buyer == ctx.accounts.bidder.key(),
+        PresaleError::UnauthorizedBuyer
+    );
+    // one bid per wallet: top-ups keep the original limit price
+    require!(
+        user_alloc.committed_lamports == 0 || user_alloc.bid_bucket == bucket,
+        PresaleError::BidPriceMismatch
+    );
+    user_alloc.bid_bucket = bucket;
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        sol_amount,
+    )?;
+
+    user_alloc.committed_lamports = user_alloc
+        .committed_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+    user_alloc.paid_lamports = user_alloc
+        .paid_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    let mut book = ctx.accounts.auction_book.load_mut()?;
+    book.buckets[bucket as usize] = book.buckets[bucket as usize]
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    // until settlement every committed lamport may have to go back
+    state.total_committed_lamports = state
+        .total_committed_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+    state.refunds_owed_lamports = state
+        .refunds_owed_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+    state.total_raised_lamports = state
+        .total_raised_lamports
+        .checked_add(sol_amount)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(BidCommitted {
+        bidder: user_alloc.buyer,
+        lamports: sol_amount,
+        bucket,
+        limit_price: state.auction_bucket_price(bucket as usize)?,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    #[account(mut, seeds = [AUCTION_BOOK_SEED], bump = auction_book.load()?.bump)]
+    pub auction_book: AccountLoader<'info, AuctionBook>,
+
+    /// treasury PDA (holds the committed lamports)
+    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.load()?.treasury_bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// bidder's allocation PDA; the bid becomes an allocation on the first claim after settlement
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        space = 8 + UserAllocation::INIT_SPACE
+    )]
+    pub user_allocation: Account<'info, UserAllocation>,
+
+    /// bidder's denylist marker address; may be left out while nobody is denylisted
+    #[account(seeds = [DENYLIST_SEED, bidder.key().as_ref()], bump)]
+    pub denylist_entry: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
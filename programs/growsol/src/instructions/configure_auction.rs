@@ -0,0 +1,113 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::auction_book::AuctionBook;
+use crate::state::presale_state::{PresaleState, PricingMode, Stage};
+use anchor_lang::prelude::*;
+
+/// Owner: turn a sale that has not started into a uniform-price auction of `supply` whole
+/// tokens. Bids commit SOL with a limit price of at least `floor_price`, on a grid of
+/// `tick` (both micro-cents per whole token).
+pub fn configure_auction(
+    ctx: Context<ConfigureAuction>,
+    floor_price: u64,
+    tick: u64,
+    supply: u64,
+) -> Result<()> {
+    let (top_price, supply_raw) = open_book(ctx, PricingMode::Auction, floor_price, tick, supply)?;
+
+    emit!(AuctionConfigured {
+        floor_price,
+        tick,
+        top_price,
+        supply_raw,
+    });
+
+    Ok(())
+}
+
+/// Owner: turn a sale that has not started into an oversubscribable fair launch of `supply`
+/// whole tokens at a fixed `price` (micro-cents per whole token). Buyers commit SOL during
+/// the window; once settled each gets min(commit, commit * cap / total committed) worth of
+/// tokens and the rest back on claim.
+pub fn configure_pro_rata(ctx: Context<ConfigureAuction>, price: u64, supply: u64) -> Result<()> {
+    // every commit sits in the floor bucket, so the auction settlement shares the supply
+    // pro rata at `price` when oversubscribed and fills everyone otherwise
+    let (_, supply_raw) = open_book(ctx, PricingMode::ProRata, price, 1, supply)?;
+
+    emit!(ProRataConfigured { price, supply_raw });
+
+    Ok(())
+}
+
+/// Switch the sale to a commit-based `mode` selling a single stage of `supply` whole tokens
+/// and create its book. Returns the top bucket's price and the supply in raw units.
+fn open_book(
+    ctx: Context<ConfigureAuction>,
+    mode: PricingMode,
+    floor_price: u64,
+    tick: u64,
+    supply: u64,
+) -> Result<(u64, u64)> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    require!(
+        Clock::get()?.unix_timestamp < state.presale_start,
+        PresaleError::SaleAlreadyStarted
+    );
+    require!(
+        floor_price > 0 && tick > 0 && supply > 0,
+        PresaleError::InvalidAuctionConfig
+    );
+    // the top bucket's price must be representable
+    state.auction_floor_price = floor_price;
+    state.auction_tick = tick;
+    let top_price = state.auction_bucket_price(AUCTION_BUCKETS - 1)?;
+
+    // like the bonding curve, commit sales sell a single stage
+    state.stages = [Stage::default(); MAX_STAGES];
+    state.stages[0] = Stage {
+        price: floor_price.div_ceil(CURVE_PRICE_SCALE as u64),
+        cap: (supply as u128)
+            .checked_mul(TOKEN_BASE)
+            .and_then(|cap| u64::try_from(cap).ok())
+            .ok_or(PresaleError::MathOverflow)?,
+        sold: 0,
+        start: 0,
+    };
+    state.stage_count = 1;
+    state.current_stage = 1;
+    state.check_supply()?;
+
+    state.pricing_mode = mode as u8;
+    state.curve_start_price = 0;
+    state.curve_end_price = 0;
+
+    ctx.accounts.auction_book.load_init()?.bump = ctx.bumps.auction_book;
+
+    Ok((top_price, state.stages[0].cap))
+}
+
+#[derive(Accounts)]
+pub struct ConfigureAuction<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [AUCTION_BOOK_SEED],
+        bump,
+        space = AuctionBook::LEN
+    )]
+    pub auction_book: AccountLoader<'info, AuctionBook>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,92 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{DistributionMode, PresaleState};
+use crate::utils::transfer_fee;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Owner deposits sale inventory into the presale vault (pre-funded mode only).
+pub fn deposit_tokens(ctx: Context<DepositTokens>, amount_raw: u64) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(
+        state.distribution_mode()? == DistributionMode::PreFundedVault,
+        PresaleError::WrongDistributionMode
+    );
+    require!(amount_raw > 0, PresaleError::ZeroPurchase);
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.owner_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.presale_token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount_raw,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    // only what actually lands in the vault counts as inventory
+    let fee_raw = transfer_fee(&ctx.accounts.mint, amount_raw)?;
+    let received_raw = amount_raw
+        .checked_sub(fee_raw)
+        .ok_or(PresaleError::MathOverflow)?;
+    state.total_deposited = state
+        .total_deposited
+        .checked_add(received_raw)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(TokensDeposited {
+        owner: state.owner,
+        amount_raw: received_raw,
+        fee_raw,
+        total_deposited: state.total_deposited,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// presale mint (pinned in presale_state)
+    #[account(address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// owner's token account funding the deposit
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// presale vault: the ATA of presale_state for the presale mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
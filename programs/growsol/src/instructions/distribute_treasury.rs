@@ -0,0 +1,124 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::instructions::withdraw_sol::treasury_proceeds;
+use crate::state::presale_state::{PresaleState, SaleStatus};
+use anchor_lang::prelude::*;
+
+/// Permissionless: splits the treasury balance above rent among the configured beneficiaries.
+/// Beneficiary wallets are passed as writable remaining accounts, in configuration order.
+pub fn distribute_treasury<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeTreasury<'info>>,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        state.status()? != SaleStatus::Cancelled,
+        PresaleError::SaleCancelled
+    );
+    require!(state.beneficiary_count > 0, PresaleError::NoBeneficiaries);
+
+    let paid_total = settle_beneficiaries(
+        &mut state,
+        &ctx.accounts.treasury.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+    require!(paid_total > 0, PresaleError::NothingToDistribute);
+
+    emit!(TreasuryDistributed {
+        caller: ctx.accounts.caller.key(),
+        amount: paid_total,
+        total_distributed: state.total_distributed,
+    });
+
+    Ok(())
+}
+
+/// Pay every configured beneficiary its share of the current treasury proceeds and return
+/// the total paid. `wallets` are the beneficiary wallets in configuration order.
+///
+/// A wallet that cannot take its payment (executable, or still below rent exemption
+/// afterwards) would fail the whole transfer, so its share is held back in the treasury as
+/// `owed` instead and paid on top of the next share it can take.
+pub fn settle_beneficiaries<'info>(
+    state: &mut PresaleState,
+    treasury: &AccountInfo<'info>,
+    wallets: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let count = state.beneficiary_count as usize;
+    require!(wallets.len() == count, PresaleError::BeneficiaryMismatch);
+
+    let held = state.beneficiaries_owed()?;
+    let distributable = treasury_proceeds(treasury, state)?.saturating_sub(held) as u128;
+    let rent = Rent::get()?;
+
+    // each share is rounded down; the dust stays in the treasury for the next call
+    let mut paid_total: u64 = 0;
+    for (beneficiary, wallet) in state.beneficiaries[..count].iter_mut().zip(wallets) {
+        require!(
+            wallet.key() == beneficiary.wallet && wallet.is_writable,
+            PresaleError::BeneficiaryMismatch
+        );
+
+        let share: u64 = distributable
+            .checked_mul(beneficiary.share_bps as u128)
+            .ok_or(PresaleError::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(PresaleError::MathOverflow)?
+            .try_into()
+            .map_err(|_| PresaleError::MathOverflow)?;
+        let amount = share
+            .checked_add(beneficiary.owed)
+            .ok_or(PresaleError::MathOverflow)?;
+        if amount == 0 {
+            continue;
+        }
+
+        let payable = !wallet.executable
+            && rent.is_exempt(wallet.lamports().saturating_add(amount), wallet.data_len());
+        if !payable {
+            beneficiary.owed = amount;
+            emit!(BeneficiaryPaymentDeferred {
+                wallet: beneficiary.wallet,
+                owed: amount,
+            });
+            continue;
+        }
+        beneficiary.owed = 0;
+
+        // treasury is program-owned, so lamports are moved directly
+        treasury.sub_lamports(amount)?;
+        wallet.add_lamports(amount)?;
+
+        beneficiary.distributed = beneficiary
+            .distributed
+            .checked_add(amount)
+            .ok_or(PresaleError::MathOverflow)?;
+        paid_total = paid_total
+            .checked_add(amount)
+            .ok_or(PresaleError::MathOverflow)?;
+
+        emit!(BeneficiaryPaid {
+            wallet: beneficiary.wallet,
+            amount,
+            total_distributed: beneficiary.distributed,
+        });
+    }
+
+    state.total_distributed = state
+        .total_distributed
+        .checked_add(paid_total)
+        .ok_or(PresaleError::MathOverflow)?;
+    Ok(paid_total)
+}
+
+#[derive(Accounts)]
+pub struct DistributeTreasury<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// treasury PDA (source of distributed lamports)
+    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.load()?.treasury_bump)]
+    pub treasury: UncheckedAccount<'info>,
+}
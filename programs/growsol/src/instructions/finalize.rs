@@ -0,0 +1,52 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{PresaleState, SaleStatus};
+use anchor_lang::prelude::*;
+
+/// Owner: close the sale for good after presale_end or a sell-out. Times, ladder and price
+/// are frozen and buy_tokens / admin_set_times reject from here on.
+pub fn finalize(ctx: Context<FinalizeSale>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(!state.is_locked()?, PresaleError::SaleLocked);
+    // a commit sale's totals are only known once it is settled
+    require!(
+        !state.is_commit_sale()? || state.is_auction_settled(),
+        PresaleError::AuctionNotSettled
+    );
+    require!(
+        state.refresh_status(clock.unix_timestamp)? == SaleStatus::Ended,
+        PresaleError::SaleNotEnded
+    );
+
+    emit!(SaleStatusChanged {
+        from: state.status()?,
+        to: SaleStatus::Finalized,
+    });
+    state.set_status(SaleStatus::Finalized);
+    state.finalized_at = clock.unix_timestamp;
+
+    emit!(SaleFinalized {
+        owner: state.owner,
+        total_allocated: state.total_allocated,
+        total_raised_lamports: state.total_raised_lamports,
+        finalized_at: state.finalized_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSale<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
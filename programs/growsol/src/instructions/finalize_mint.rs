@@ -0,0 +1,141 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{DistributionMode, PresaleState, SaleStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface,
+};
+
+/// Owner: once the sale is finalized, pre-mints every unclaimed allocation into the vault, mints the
+/// reserved tranche to an allowlisted recipient and revokes the mint authority for good.
+pub fn finalize_mint(ctx: Context<FinalizeMint>) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(
+        state.status()? == SaleStatus::Finalized,
+        PresaleError::SaleNotFinalized
+    );
+    require!(
+        state.distribution_mode()? == DistributionMode::MintOnClaim,
+        PresaleError::WrongDistributionMode
+    );
+    require!(!state.is_mint_finalized(), PresaleError::MintFinalized);
+    require!(
+        state.is_payout_destination(&ctx.accounts.reserve_recipient.key()),
+        PresaleError::DestinationNotAllowed
+    );
+
+    let unclaimed_raw = state.unclaimed_raw()?;
+    let reserved_raw = state.reserved_supply;
+
+    let mint_auth_seeds = &[MINT_SEED, &[state.mint_bump]];
+    let signer_seeds = &[&mint_auth_seeds[..]];
+
+    // claims keep working from the vault once minting is no longer possible
+    if unclaimed_raw > 0 {
+        state.record_mint(unclaimed_raw)?;
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.presale_token_account.to_account_info(),
+                    authority: ctx.accounts.mint_auth.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            unclaimed_raw,
+        )?;
+    }
+
+    if reserved_raw > 0 {
+        state.record_mint(reserved_raw)?;
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.reserve_token_account.to_account_info(),
+                    authority: ctx.accounts.mint_auth.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            reserved_raw,
+        )?;
+    }
+
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: ctx.accounts.mint_auth.to_account_info(),
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    state.mint_finalized = 1;
+
+    emit!(MintFinalized {
+        owner: state.owner,
+        unclaimed_raw,
+        reserved_raw,
+        reserve_recipient: ctx.accounts.reserve_recipient.key(),
+        total_minted: state.total_minted,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeMint<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// mint auth PDA (must match presale_state.mint_bump)
+    #[account(seeds = [MINT_SEED], bump = presale_state.load()?.mint_bump)]
+    pub mint_auth: UncheckedAccount<'info>,
+
+    /// presale mint (pinned in presale_state)
+    #[account(mut, address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// presale vault (receives all unclaimed allocations)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// reserve recipient wallet (owner or an allowlisted payout destination)
+    pub reserve_recipient: UncheckedAccount<'info>,
+
+    /// reserve recipient's ATA (will be created if needed, paid by owner)
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = reserve_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub reserve_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,205 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::{DistributionMode, PresaleState, SaleStatus, UnsoldAction};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Owner: once the sale is finalized, burns unsold inventory or delivers it to an allowlisted reserve or
+/// liquidity wallet. Can only be decided once.
+pub fn finalize_unsold(ctx: Context<FinalizeUnsold>, action: UnsoldAction) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(
+        state.status()? == SaleStatus::Finalized,
+        PresaleError::SaleNotFinalized
+    );
+    require!(
+        state.unsold_action()?.is_none(),
+        PresaleError::UnsoldAlreadyFinalized
+    );
+
+    let destination = match action {
+        UnsoldAction::Burn => Pubkey::default(),
+        UnsoldAction::SendToReserve | UnsoldAction::RollToLiquidity => {
+            let destination = ctx
+                .accounts
+                .destination
+                .as_ref()
+                .ok_or(ErrorCode::AccountNotEnoughKeys)?
+                .key();
+            // the receiving ATA has to come with the wallet
+            require!(
+                ctx.accounts.destination_token_account.is_some(),
+                ErrorCode::AccountNotEnoughKeys
+            );
+            require!(
+                state.is_payout_destination(&destination),
+                PresaleError::DestinationNotAllowed
+            );
+            destination
+        }
+    };
+
+    // mint mode: capacity never sold; vault mode: inventory not backing any allocation
+    let mode = state.distribution_mode()?;
+    let unsold_raw = match mode {
+        DistributionMode::MintOnClaim => state
+            .sale_supply()?
+            .checked_sub(state.total_allocated)
+            .ok_or(PresaleError::MathOverflow)?,
+        DistributionMode::PreFundedVault => ctx
+            .accounts
+            .presale_token_account
+            .amount
+            .saturating_sub(state.unclaimed_raw()?),
+    };
+
+    if action == UnsoldAction::RollToLiquidity {
+        state.liquidity_tranche = state
+            .liquidity_tranche
+            .checked_add(unsold_raw)
+            .ok_or(PresaleError::MathOverflow)?;
+    }
+    state.set_unsold_action(action);
+    state.unsold_amount = unsold_raw;
+    state.unsold_destination = destination;
+
+    if unsold_raw > 0 {
+        match (mode, action) {
+            (DistributionMode::MintOnClaim, UnsoldAction::Burn) => {
+                // never minted: shrink the hard cap so it can never be
+                state.max_supply = state
+                    .max_supply
+                    .checked_sub(unsold_raw)
+                    .ok_or(PresaleError::MathOverflow)?;
+            }
+            (DistributionMode::MintOnClaim, _) => {
+                require!(!state.is_mint_finalized(), PresaleError::MintFinalized);
+                state.record_mint(unsold_raw)?;
+
+                let mint_auth_seeds = &[MINT_SEED, &[state.mint_bump]];
+                let signer_seeds = &[&mint_auth_seeds[..]];
+                token_interface::mint_to(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        MintTo {
+                            mint: ctx.accounts.mint.to_account_info(),
+                            to: destination_token_account(ctx.accounts)?,
+                            authority: ctx.accounts.mint_auth.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    unsold_raw,
+                )?;
+            }
+            (DistributionMode::PreFundedVault, _) => {
+                // presale_state signs the vault CPI, so its data must not stay borrowed
+                let bump = state.bump;
+                drop(state);
+                let presale_state_seeds = &[PRESALE_STATE_SEED, &[bump]];
+                let signer_seeds = &[&presale_state_seeds[..]];
+
+                if action == UnsoldAction::Burn {
+                    token_interface::burn(
+                        CpiContext::new_with_signer(
+                            ctx.accounts.token_program.to_account_info(),
+                            Burn {
+                                mint: ctx.accounts.mint.to_account_info(),
+                                from: ctx.accounts.presale_token_account.to_account_info(),
+                                authority: ctx.accounts.presale_state.to_account_info(),
+                            },
+                            signer_seeds,
+                        ),
+                        unsold_raw,
+                    )?;
+                } else {
+                    token_interface::transfer_checked(
+                        CpiContext::new_with_signer(
+                            ctx.accounts.token_program.to_account_info(),
+                            TransferChecked {
+                                from: ctx.accounts.presale_token_account.to_account_info(),
+                                mint: ctx.accounts.mint.to_account_info(),
+                                to: destination_token_account(ctx.accounts)?,
+                                authority: ctx.accounts.presale_state.to_account_info(),
+                            },
+                            signer_seeds,
+                        ),
+                        unsold_raw,
+                        ctx.accounts.mint.decimals,
+                    )?;
+                }
+            }
+        }
+    }
+
+    emit!(UnsoldFinalized {
+        owner: ctx.accounts.owner.key(),
+        action,
+        amount_raw: unsold_raw,
+        destination,
+    });
+
+    Ok(())
+}
+
+/// Receiving ATA for the transfer actions (checked present when the action is decided).
+fn destination_token_account<'info>(
+    accounts: &FinalizeUnsold<'info>,
+) -> Result<AccountInfo<'info>> {
+    Ok(accounts
+        .destination_token_account
+        .as_ref()
+        .ok_or(ErrorCode::AccountNotEnoughKeys)?
+        .to_account_info())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeUnsold<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// mint auth PDA (must match presale_state.mint_bump)
+    #[account(seeds = [MINT_SEED], bump = presale_state.load()?.mint_bump)]
+    pub mint_auth: UncheckedAccount<'info>,
+
+    /// presale mint (pinned in presale_state)
+    #[account(mut, address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// presale vault: the ATA of presale_state for the presale mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// reserve / liquidity wallet (owner or an allowlisted payout destination); omitted for Burn
+    pub destination: Option<UncheckedAccount<'info>>,
+
+    /// destination's ATA (will be created if needed, paid by owner); omitted for Burn
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = destination,
+        associated_token::token_program = token_program,
+    )]
+    pub destination_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
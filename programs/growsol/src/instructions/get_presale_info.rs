@@ -0,0 +1,76 @@
+use crate::constants::*;
+use crate::state::presale_state::{PresaleState, PricingMode, SaleStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+/// Addresses and sale parameters returned by `get_presale_info`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PresaleInfo {
+    pub presale_state: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub mint_auth: Pubkey,
+    pub vault: Pubkey,
+    pub treasury: Pubkey,
+    pub presale_start: i64,
+    pub presale_end: i64,
+    pub paused: bool,
+    pub status: SaleStatus,
+    pub current_stage: u8,
+    pub total_allocated: u64,
+    pub pricing_mode: PricingMode,
+    /// bonding-curve start / end price (micro-cents per token; 0 for ladder sales)
+    pub curve_start_price: u64,
+    pub curve_end_price: u64,
+    /// auction clearing price (micro-cents per token; 0 until settled)
+    pub clearing_price: u64,
+    /// bonus pool size and how much of it buyers have been credited (raw)
+    pub bonus_pool_cap: u64,
+    pub bonus_allocated: u64,
+}
+
+/// View: returns the presale's mint, vault and treasury addresses via return data.
+pub fn get_presale_info(ctx: Context<GetPresaleInfo>) -> Result<PresaleInfo> {
+    let state = ctx.accounts.presale_state.load()?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let mint_auth =
+        Pubkey::create_program_address(&[MINT_SEED, &[state.mint_bump]], ctx.program_id)
+            .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    let treasury =
+        Pubkey::create_program_address(&[TREASURY_SEED, &[state.treasury_bump]], ctx.program_id)
+            .map_err(|_| ErrorCode::ConstraintSeeds)?;
+
+    Ok(PresaleInfo {
+        presale_state: ctx.accounts.presale_state.key(),
+        owner: state.owner,
+        mint: state.mint,
+        token_program: state.token_program,
+        mint_auth,
+        vault: get_associated_token_address_with_program_id(
+            &ctx.accounts.presale_state.key(),
+            &state.mint,
+            &state.token_program,
+        ),
+        treasury,
+        presale_start: state.presale_start,
+        presale_end: state.presale_end,
+        paused: state.is_paused(),
+        status: state.effective_status(now)?,
+        current_stage: state.active_stage(now),
+        total_allocated: state.total_allocated,
+        pricing_mode: state.pricing_mode()?,
+        curve_start_price: state.curve_start_price,
+        curve_end_price: state.curve_end_price,
+        clearing_price: state.clearing_price,
+        bonus_pool_cap: state.bonus_pool_cap,
+        bonus_allocated: state.bonus_allocated,
+    })
+}
+
+#[derive(Accounts)]
+pub struct GetPresaleInfo<'info> {
+    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
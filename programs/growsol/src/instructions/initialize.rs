@@ -1,9 +1,13 @@
 use crate::constants::*;
+use crate::errors::PresaleError;
 use crate::events::*;
-use crate::state::presale_state::PresaleState;
+use crate::state::presale_state::{
+    Beneficiary, BondingCurve, DistributionMode, ExpiredStagePolicy, PresaleState, PricingMode,
+    SaleStatus, Stage,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use anchor_lang::solana_program::sysvar::rent::Rent;
 
 pub fn initialize(
@@ -11,8 +15,11 @@ pub fn initialize(
     usd_per_sol: u64,
     presale_start_ts: i64,
     presale_end_ts: i64,
+    max_supply: u64,
+    reserved_supply: u64,
+    curve: Option<BondingCurve>,
 ) -> Result<()> {
-    let state = &mut ctx.accounts.presale_state;
+    let mut state = ctx.accounts.presale_state.load_init()?;
 
     // store bumps for later verification
     state.bump = ctx.bumps.presale_state;
@@ -21,37 +28,32 @@ pub fn initialize(
 
     // basic metadata
     state.owner = ctx.accounts.owner.key();
-    state.usd_per_sol = usd_per_sol;
-    state.presale_start = presale_start_ts;
-    state.presale_end = presale_end_ts;
-    state.paused = false;
-    state.current_stage = 1;
-
-    // stage prices (in cents)
-    state.stage_1_price = 1;
-    state.stage_2_price = 2;
-    state.stage_3_price = 3;
-    state.stage_4_price = 4;
-    state.stage_5_price = 5;
-
-    // stage caps (raw units = tokens * 10^decimals)
-    state.stage_1_cap = (STAGE_1_TOKENS.checked_mul(TOKEN_BASE).unwrap()) as u64;
-    state.stage_2_cap = (STAGE_2_TOKENS.checked_mul(TOKEN_BASE).unwrap()) as u64;
-    state.stage_3_cap = (STAGE_3_TOKENS.checked_mul(TOKEN_BASE).unwrap()) as u64;
-    state.stage_4_cap = (STAGE_4_TOKENS.checked_mul(TOKEN_BASE).unwrap()) as u64;
-    state.stage_5_cap = (STAGE_5_TOKENS.checked_mul(TOKEN_BASE).unwrap()) as u64;
-
-    state.stage_1_sold = 0;
-    state.stage_2_sold = 0;
-    state.stage_3_sold = 0;
-    state.stage_4_sold = 0;
-    state.stage_5_sold = 0;
-
-    state.total_allocated = 0;
+    state.mint = ctx.accounts.mint.key();
+    state.token_program = ctx.accounts.token_program.key();
+    state.distribution_mode = DistributionMode::MintOnClaim as u8;
+    configure_sale(
+        &mut state,
+        usd_per_sol,
+        presale_start_ts,
+        presale_end_ts,
+        curve,
+    )?;
+
+    // every sale cap plus the reserve must fit under the hard supply cap
+    require!(
+        state
+            .sale_supply()?
+            .checked_add(reserved_supply)
+            .ok_or(PresaleError::MathOverflow)?
+            <= max_supply,
+        PresaleError::InvalidSupplyConfig
+    );
+    state.max_supply = max_supply;
+    state.reserved_supply = reserved_supply;
 
     msg!(
         "Initialized presale_state {} and presale_token_account {}",
-        state.key(),
+        ctx.accounts.presale_state.key(),
         ctx.accounts.presale_token_account.key()
     );
 
@@ -64,8 +66,96 @@ pub fn initialize(
     Ok(())
 }
 
+/// Sale parameters and counters shared by every initialize flavour. With `curve` the sale
+/// prices on a linear bonding curve instead of the default ladder.
+pub(crate) fn configure_sale(
+    state: &mut PresaleState,
+    usd_per_sol: u64,
+    presale_start_ts: i64,
+    presale_end_ts: i64,
+    curve: Option<BondingCurve>,
+) -> Result<()> {
+    state.usd_per_sol = usd_per_sol;
+    state.presale_start = presale_start_ts;
+    state.presale_end = presale_end_ts;
+    state.paused = 0;
+    state.current_stage = 1;
+    state.set_status(SaleStatus::Pending);
+    state.version = PRESALE_STATE_VERSION;
+    state.finalized_at = 0;
+    state.total_raised_lamports = 0;
+    state.total_refunded_lamports = 0;
+
+    // default ladder: prices in cents, caps in raw units (tokens * 10^decimals); no time
+    // schedule until admin_set_stage_schedule
+    let ladder = [
+        (1, STAGE_1_TOKENS),
+        (2, STAGE_2_TOKENS),
+        (3, STAGE_3_TOKENS),
+        (4, STAGE_4_TOKENS),
+        (5, STAGE_5_TOKENS),
+    ];
+    state.stages = [Stage::default(); MAX_STAGES];
+    for (slot, (price, tokens)) in state.stages.iter_mut().zip(ladder) {
+        *slot = Stage {
+            price,
+            cap: (tokens.checked_mul(TOKEN_BASE).unwrap()) as u64,
+            sold: 0,
+            start: 0,
+        };
+    }
+    state.stage_count = ladder.len() as u8;
+    state.expired_stage_policy = ExpiredStagePolicy::Discard as u8;
+
+    state.pricing_mode = PricingMode::Ladder as u8;
+    state.curve_start_price = 0;
+    state.curve_end_price = 0;
+    if let Some(curve) = curve {
+        require!(
+            curve.start_price > 0 && curve.end_price >= curve.start_price && curve.supply > 0,
+            PresaleError::InvalidCurveConfig
+        );
+        // the curve is a single stage: its cap is the curve supply, its price the opening
+        // price rounded up to cents (informational only)
+        state.stages = [Stage::default(); MAX_STAGES];
+        state.stages[0] = Stage {
+            price: curve.start_price.div_ceil(CURVE_PRICE_SCALE as u64),
+            cap: (curve.supply as u128)
+                .checked_mul(TOKEN_BASE)
+                .and_then(|cap| u64::try_from(cap).ok())
+                .ok_or(PresaleError::MathOverflow)?,
+            sold: 0,
+            start: 0,
+        };
+        state.stage_count = 1;
+        state.pricing_mode = PricingMode::LinearCurve as u8;
+        state.curve_start_price = curve.start_price;
+        state.curve_end_price = curve.end_price;
+    }
+
+    state.total_allocated = 0;
+
+    state.total_minted = 0;
+    state.total_claimed = 0;
+    state.total_deposited = 0;
+    state.mint_finalized = 0;
+
+    state.unsold_action = 0;
+    state.unsold_amount = 0;
+    state.unsold_destination = Pubkey::default();
+    state.liquidity_tranche = 0;
+
+    state.payout_destination_count = 0;
+    state.payout_destinations = [Pubkey::default(); MAX_PAYOUT_DESTINATIONS];
+
+    state.beneficiary_count = 0;
+    state.beneficiaries = [Beneficiary::default(); MAX_BENEFICIARIES];
+    state.total_distributed = 0;
+    Ok(())
+}
+
 #[derive(Accounts)]
-#[instruction(usd_per_sol: u64, presale_start_ts: i64, presale_end_ts: i64)]
+#[instruction(usd_per_sol: u64, presale_start_ts: i64, presale_end_ts: i64, max_supply: u64, reserved_supply: u64)]
 pub struct Initialize<'info> {
     /// Owner / initializer
     #[account(mut)]
@@ -77,9 +167,9 @@ pub struct Initialize<'info> {
         payer = owner,
         seeds = [PRESALE_STATE_SEED],
         bump,
-        space = 8 + PRESALE_SIZE
+        space = PresaleState::LEN
     )]
-    pub presale_state: Account<'info, PresaleState>,
+    pub presale_state: AccountLoader<'info, PresaleState>,
 
     /// Token mint (authority set to mint_auth PDA)
     #[account(
@@ -87,8 +177,9 @@ pub struct Initialize<'info> {
         payer = owner,
         mint::decimals = 9,
         mint::authority = mint_auth,
+        mint::token_program = token_program,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     /// Mint authority PDA (will be used as mint authority via seeds)
     #[account(
@@ -116,10 +207,11 @@ pub struct Initialize<'info> {
         payer = owner,
         associated_token::mint = mint,
         associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
     )]
-    pub presale_token_account: Account<'info, TokenAccount>,
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -0,0 +1,112 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::instructions::initialize::configure_sale;
+use crate::state::presale_state::{BondingCurve, DistributionMode, PresaleState};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Initialize a pre-funded presale around an existing fixed-supply mint. The program never
+/// holds mint authority; the owner deposits inventory with `deposit_tokens`.
+pub fn initialize_with_mint(
+    ctx: Context<InitializeWithMint>,
+    usd_per_sol: u64,
+    presale_start_ts: i64,
+    presale_end_ts: i64,
+    curve: Option<BondingCurve>,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_init()?;
+
+    // store bumps for later verification
+    state.bump = ctx.bumps.presale_state;
+    state.mint_bump = ctx.bumps.mint_auth;
+    state.treasury_bump = ctx.bumps.treasury;
+
+    // basic metadata
+    state.owner = ctx.accounts.owner.key();
+    state.mint = ctx.accounts.mint.key();
+    state.token_program = ctx.accounts.token_program.key();
+    state.distribution_mode = DistributionMode::PreFundedVault as u8;
+    configure_sale(
+        &mut state,
+        usd_per_sol,
+        presale_start_ts,
+        presale_end_ts,
+        curve,
+    )?;
+
+    // supply is fixed by the existing mint; nothing is reserved for minting
+    state.max_supply = ctx.accounts.mint.supply;
+    state.reserved_supply = 0;
+    state.check_supply()?;
+
+    msg!(
+        "Initialized pre-funded presale_state {} and presale_token_account {}",
+        ctx.accounts.presale_state.key(),
+        ctx.accounts.presale_token_account.key()
+    );
+
+    emit!(Initialized {
+        owner: state.owner,
+        start_ts: state.presale_start,
+        end_ts: state.presale_end,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeWithMint<'info> {
+    /// Owner / initializer
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Presale state PDA
+    #[account(
+        init,
+        payer = owner,
+        seeds = [PRESALE_STATE_SEED],
+        bump,
+        space = PresaleState::LEN
+    )]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// Existing token mint (ladder math assumes TOKEN_DECIMALS)
+    #[account(constraint = mint.decimals == TOKEN_DECIMALS @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Mint authority PDA (created for a uniform PDA set; never a mint authority here)
+    #[account(
+        init,
+        seeds = [MINT_SEED],
+        bump,
+        payer = owner,
+        space = 8,
+    )]
+    pub mint_auth: UncheckedAccount<'info>,
+
+    /// Treasury PDA (holds SOL)
+    #[account(
+        init,
+        seeds = [TREASURY_SEED],
+        bump,
+        payer = owner,
+        space = 8,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// ATA owned by presale_state to hold deposited inventory
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,217 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::legacy::PresaleStateV0;
+use crate::state::presale_state::PresaleState;
+use crate::state::user_state::UserAllocation;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+/// Owner: realloc the presale state to the current layout and upgrade it in place.
+///
+/// Layouts are told apart by size (see `state::legacy`):
+/// - v0: the Borsh layout from before versioning; it did not record the mint, so the caller
+///   passes it and it must still have `mint_auth` as its mint authority
+/// - v1 onwards: the current zero-copy layout; later fields are carved out of `reserved`
+pub fn migrate_presale_state(ctx: Context<MigratePresaleState>) -> Result<()> {
+    let clock = Clock::get()?;
+    let owner = ctx.accounts.owner.key();
+    let mint = &ctx.accounts.mint;
+    let info = ctx.accounts.presale_state.to_account_info();
+
+    let from_version = if info.data_len() == PresaleState::LEN {
+        let state = ctx.accounts.presale_state.load()?;
+        require!(owner == state.owner, PresaleError::Unauthorized);
+        require!(mint.key() == state.mint, PresaleError::InvalidMint);
+        require!(
+            state.version < PRESALE_STATE_VERSION,
+            PresaleError::AlreadyMigrated
+        );
+        state.version
+    } else {
+        require!(
+            info.data_len() == PresaleStateV0::LEN,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        let legacy = PresaleStateV0::deserialize(&mut &info.try_borrow_data()?[8..])?;
+        require!(owner == legacy.owner, PresaleError::Unauthorized);
+        let mint_auth =
+            Pubkey::create_program_address(&[MINT_SEED, &[legacy.mint_bump]], &crate::ID)
+                .map_err(|_| PresaleError::InvalidMint)?;
+        require!(
+            mint.mint_authority == COption::Some(mint_auth),
+            PresaleError::InvalidMint
+        );
+
+        grow_to(
+            &info,
+            &ctx.accounts.owner,
+            &ctx.accounts.system_program,
+            PresaleState::LEN,
+        )?;
+        // the old bytes are replaced wholesale; the discriminator stays the same
+        info.try_borrow_mut_data()?[8..].fill(0);
+        let mut state = ctx.accounts.presale_state.load_mut()?;
+        legacy.write_to(
+            &mut state,
+            mint.key(),
+            ctx.accounts.token_program.key(),
+            mint.supply,
+        )?;
+        state.refresh_status(clock.unix_timestamp)?;
+        0
+    };
+
+    // v1 -> v2 and later: upgrade steps on the current layout go here
+    ctx.accounts.presale_state.load_mut()?.version = PRESALE_STATE_VERSION;
+
+    emit!(AccountMigrated {
+        account: ctx.accounts.presale_state.key(),
+        from_version,
+        to_version: PRESALE_STATE_VERSION,
+    });
+
+    Ok(())
+}
+
+/// Buyer: realloc an allocation to the current layout and upgrade it in place.
+/// Version 0 (before versioning) ends at `bump`.
+pub fn migrate_user_allocation(ctx: Context<MigrateUserAllocation>) -> Result<()> {
+    let buyer = ctx.accounts.buyer.key();
+    let bump = ctx.bumps.user_allocation;
+    let mut from_version = 0;
+
+    upgrade_in_place::<UserAllocation>(
+        &ctx.accounts.user_allocation,
+        &ctx.accounts.buyer,
+        &ctx.accounts.system_program,
+        8 + UserAllocation::INIT_SPACE,
+        |user_alloc| {
+            require!(user_alloc.buyer == buyer, PresaleError::UnauthorizedBuyer);
+            require!(
+                user_alloc.version < USER_ALLOC_VERSION,
+                PresaleError::AlreadyMigrated
+            );
+            from_version = user_alloc.version;
+
+            // v0 -> v1: every field after `bump` starts at zero; v0 did not record what the
+            // buyer paid, so `paid_lamports` (and with it a cancelled sale's refund) is zero.
+            // v0 never stored the bump either, and every later instruction checks it
+            if from_version == 0 {
+                user_alloc.bump = bump;
+            }
+
+            user_alloc.version = USER_ALLOC_VERSION;
+            Ok(())
+        },
+    )?;
+
+    emit!(AccountMigrated {
+        account: ctx.accounts.user_allocation.key(),
+        from_version,
+        to_version: USER_ALLOC_VERSION,
+    });
+
+    Ok(())
+}
+
+/// Grow (or shrink) `account` to `new_len`; the payer tops up rent. New bytes are zeroed,
+/// i.e. every appended field starts at its default.
+fn grow_to<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let top_up = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.resize(new_len)?;
+    Ok(())
+}
+
+/// Grow a Borsh account to `new_len`, deserialize it, apply `upgrade` and write it back.
+fn upgrade_in_place<'info, T>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+    upgrade: impl FnOnce(&mut T) -> Result<()>,
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Discriminator,
+{
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    require!(
+        account.try_borrow_data()?.starts_with(T::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    if account.data_len() < new_len {
+        grow_to(account, payer, system_program, new_len)?;
+    }
+
+    let mut value = T::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    upgrade(&mut value)?;
+    value.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePresaleState<'info> {
+    /// owner; pays for the extra rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// presale state in any layout version (only owner and discriminator are checked here)
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// sale token mint; pinned into a v0 state, checked against the pinned one otherwise
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserAllocation<'info> {
+    /// allocation owner; pays for the extra rent
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(seeds = [PRESALE_STATE_SEED], bump)]
+    pub presale_state: UncheckedAccount<'info>,
+
+    /// allocation in any layout version (checked and deserialized by the handler)
+    #[account(
+        mut,
+        seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub user_allocation: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
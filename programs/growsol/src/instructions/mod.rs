@@ -5,6 +5,29 @@ pub mod withdraw_sol;
 pub mod withdraw_token;
 pub mod admin_set_times;
 pub mod pause_sale;
+pub mod payout_destinations;
+pub mod set_beneficiaries;
+pub mod distribute_treasury;
+pub mod get_presale_info;
+pub mod finalize_mint;
+pub mod initialize_with_mint;
+pub mod deposit_tokens;
+pub mod finalize_unsold;
+pub mod token_metadata;
+pub mod finalize;
+pub mod cancel_sale;
+pub mod refund;
+pub mod close_allocation;
+pub mod migrate;
+pub mod admin_set_stage_schedule;
+pub mod admin_stages;
+pub mod configure_auction;
+pub mod commit_bid;
+pub mod settle_auction;
+pub mod admin_set_extension_rule;
+pub mod admin_set_rate_limits;
+pub mod admin_denylist;
+pub mod admin_set_bonus;
 
 pub use initialize::*;
 pub use buy_tokens::*;
@@ -13,3 +36,26 @@ pub use withdraw_sol::*;
 pub use withdraw_token::*;
 pub use admin_set_times::*;
 pub use pause_sale::*;
+pub use payout_destinations::*;
+pub use set_beneficiaries::*;
+pub use distribute_treasury::*;
+pub use get_presale_info::*;
+pub use finalize_mint::*;
+pub use initialize_with_mint::*;
+pub use deposit_tokens::*;
+pub use finalize_unsold::*;
+pub use token_metadata::*;
+pub use finalize::*;
+pub use cancel_sale::*;
+pub use refund::*;
+pub use close_allocation::*;
+pub use migrate::*;
+pub use admin_set_stage_schedule::*;
+pub use admin_stages::*;
+pub use configure_auction::*;
+pub use commit_bid::*;
+pub use settle_auction::*;
+pub use admin_set_extension_rule::*;
+pub use admin_set_rate_limits::*;
+pub use admin_denylist::*;
+pub use admin_set_bonus::*;
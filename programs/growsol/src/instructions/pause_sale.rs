@@ -5,23 +5,23 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 
 pub fn pause_sale(ctx: Context<AdminToggleSale>) -> Result<()> {
-    let state = &mut ctx.accounts.presale_state;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
     require!(
         ctx.accounts.owner.key() == state.owner,
         PresaleError::Unauthorized
     );
-    state.paused = true;
+    state.paused = 1;
     emit!(SalePaused { owner: state.owner });
     Ok(())
 }
 
 pub fn resume_sale(ctx: Context<AdminToggleSale>) -> Result<()> {
-    let state = &mut ctx.accounts.presale_state;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
     require!(
         ctx.accounts.owner.key() == state.owner,
         PresaleError::Unauthorized
     );
-    state.paused = false;
+    state.paused = 0;
     emit!(SaleResumed { owner: state.owner });
     Ok(())
 }
@@ -31,6 +31,6 @@ pub struct AdminToggleSale<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.bump)]
-    pub presale_state: Account<'info, PresaleState>,
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
 }
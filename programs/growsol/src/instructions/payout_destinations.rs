@@ -0,0 +1,73 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::PresaleState;
+use anchor_lang::prelude::*;
+
+pub fn add_payout_destination(
+    ctx: Context<AdminPayoutDestination>,
+    destination: Pubkey,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(
+        !state.payout_destinations().contains(&destination),
+        PresaleError::DestinationAlreadyAllowed
+    );
+
+    let count = state.payout_destination_count as usize;
+    require!(
+        count < MAX_PAYOUT_DESTINATIONS,
+        PresaleError::DestinationListFull
+    );
+
+    state.payout_destinations[count] = destination;
+    state.payout_destination_count += 1;
+
+    emit!(PayoutDestinationAdded {
+        owner: state.owner,
+        destination,
+    });
+    Ok(())
+}
+
+pub fn remove_payout_destination(
+    ctx: Context<AdminPayoutDestination>,
+    destination: Pubkey,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+
+    let index = state
+        .payout_destinations()
+        .iter()
+        .position(|d| *d == destination)
+        .ok_or(PresaleError::DestinationNotAllowed)?;
+
+    // swap-remove: move the last active entry into the freed slot
+    let last = state.payout_destination_count as usize - 1;
+    state.payout_destinations[index] = state.payout_destinations[last];
+    state.payout_destinations[last] = Pubkey::default();
+    state.payout_destination_count -= 1;
+
+    emit!(PayoutDestinationRemoved {
+        owner: state.owner,
+        destination,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdminPayoutDestination<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+}
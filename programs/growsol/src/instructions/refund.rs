@@ -0,0 +1,100 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::instructions::withdraw_sol::treasury_withdrawable;
+use crate::state::auction_book::AuctionBook;
+use crate::state::presale_state::{PresaleState, SaleStatus};
+use crate::state::user_state::UserAllocation;
+use anchor_lang::prelude::*;
+
+/// Buyer: after cancellation, get back exactly the lamports paid and close the allocation.
+pub fn refund(ctx: Context<Refund>) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    let user_alloc = &ctx.accounts.user_allocation;
+
+    require!(
+        user_alloc.buyer == ctx.accounts.buyer.key(),
+        PresaleError::UnauthorizedBuyer
+    );
+    require!(
+        state.status()? == SaleStatus::Cancelled,
+        PresaleError::SaleNotCancelled
+    );
+    require!(user_alloc.claimed_raw == 0, PresaleError::AlreadyClaimed);
+
+    let lamports = user_alloc.paid_lamports;
+    require!(lamports > 0, PresaleError::NothingToRefund);
+    require!(
+        lamports <= treasury_withdrawable(&ctx.accounts.treasury.to_account_info())?,
+        PresaleError::InsufficientFunds
+    );
+
+    // a settled bid that was never claimed has its fill counted in total_allocated but not
+    // yet in the allocation, and only the part the fill did not cost is still owed to it
+    let committed = user_alloc.committed_lamports;
+    let (fill_raw, owed_lamports) = if committed > 0 && state.is_auction_settled() {
+        let book = ctx
+            .accounts
+            .auction_book
+            .as_ref()
+            .ok_or(ErrorCode::AccountNotEnoughKeys)?
+            .load()?;
+        let (fill_raw, cost) = state.auction_fill(&book, committed, user_alloc.bid_bucket)?;
+        (fill_raw, committed - cost)
+    } else {
+        (0, committed)
+    };
+
+    // treasury is program-owned, so lamports are moved directly
+    ctx.accounts.treasury.sub_lamports(lamports)?;
+    ctx.accounts.buyer.add_lamports(lamports)?;
+
+    state.total_allocated = state
+        .total_allocated
+        .checked_sub(user_alloc.allocated_raw)
+        .and_then(|total| total.checked_sub(fill_raw))
+        .ok_or(PresaleError::MathOverflow)?;
+    state.bonus_allocated = state
+        .bonus_allocated
+        .checked_sub(user_alloc.bonus_raw)
+        .ok_or(PresaleError::MathOverflow)?;
+    // what the bid was still owed is paid out here
+    state.refunds_owed_lamports = state.refunds_owed_lamports.saturating_sub(owed_lamports);
+    state.total_refunded_lamports = state
+        .total_refunded_lamports
+        .checked_add(lamports)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(Refunded {
+        buyer: user_alloc.buyer,
+        lamports,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// treasury PDA (source of the refund)
+    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.load()?.treasury_bump)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// auction book; only needed to refund a settled bid that was never claimed
+    #[account(seeds = [AUCTION_BOOK_SEED], bump = auction_book.load()?.bump)]
+    pub auction_book: Option<AccountLoader<'info, AuctionBook>>,
+
+    /// buyer's allocation, closed to the buyer once refunded
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [USER_ALLOC_SEED, presale_state.key().as_ref(), buyer.key().as_ref()],
+        bump = user_allocation.bump
+    )]
+    pub user_allocation: Account<'info, UserAllocation>,
+}
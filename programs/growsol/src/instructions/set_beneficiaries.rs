@@ -0,0 +1,124 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::instructions::distribute_treasury::settle_beneficiaries;
+use crate::instructions::withdraw_sol::treasury_proceeds;
+use crate::state::presale_state::{Beneficiary, PresaleState, SaleStatus};
+use anchor_lang::prelude::*;
+
+/// One entry of the revenue split as supplied by the owner.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BeneficiaryShare {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+}
+
+/// Remaining accounts: the new wallets in `shares` order, so they can be checked to take
+/// lamports, then the current beneficiary wallets (writable, in configuration order), under
+/// whose split proceeds already in the treasury are paid out before it changes (none needed
+/// while nothing is pending). Lamports still owed to a wallet that leaves the split go back
+/// to the treasury proceeds.
+pub fn set_beneficiaries<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetBeneficiaries<'info>>,
+    shares: Vec<BeneficiaryShare>,
+) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+    require!(
+        shares.len() <= MAX_BENEFICIARIES,
+        PresaleError::TooManyBeneficiaries
+    );
+
+    // an empty list disables the split; otherwise shares must cover exactly 100%
+    let mut total_bps: u64 = 0;
+    for (i, share) in shares.iter().enumerate() {
+        require!(share.share_bps > 0, PresaleError::InvalidBeneficiaryShares);
+        require!(
+            !shares[..i].iter().any(|s| s.wallet == share.wallet),
+            PresaleError::InvalidBeneficiaryShares
+        );
+        total_bps = total_bps
+            .checked_add(share.share_bps as u64)
+            .ok_or(PresaleError::MathOverflow)?;
+    }
+    require!(
+        shares.is_empty() || total_bps == BPS_DENOMINATOR,
+        PresaleError::InvalidBeneficiaryShares
+    );
+
+    require!(
+        ctx.remaining_accounts.len() >= shares.len(),
+        PresaleError::BeneficiaryMismatch
+    );
+    let (new_wallets, current_wallets) = ctx.remaining_accounts.split_at(shares.len());
+    let treasury = ctx.accounts.treasury.to_account_info();
+    for (share, wallet) in shares.iter().zip(new_wallets) {
+        require!(
+            wallet.key() == share.wallet,
+            PresaleError::BeneficiaryMismatch
+        );
+        // programs cannot be credited, and paying the treasury would only move the count
+        require!(
+            share.wallet != Pubkey::default()
+                && share.wallet != treasury.key()
+                && !wallet.executable,
+            PresaleError::InvalidBeneficiaryWallet
+        );
+    }
+
+    // a cancelled sale's treasury belongs to the buyers awaiting refunds
+    if state.beneficiary_count > 0
+        && state.status()? != SaleStatus::Cancelled
+        && treasury_proceeds(&treasury, &state)? > 0
+    {
+        let paid_total = settle_beneficiaries(&mut state, &treasury, current_wallets)?;
+        emit!(TreasuryDistributed {
+            caller: state.owner,
+            amount: paid_total,
+            total_distributed: state.total_distributed,
+        });
+    }
+
+    // carry running totals and held-back payments over for wallets that stay in the split
+    let mut beneficiaries = [Beneficiary::default(); MAX_BENEFICIARIES];
+    for (slot, share) in beneficiaries.iter_mut().zip(shares.iter()) {
+        let (distributed, owed) = state
+            .beneficiaries()
+            .iter()
+            .find(|b| b.wallet == share.wallet)
+            .map(|b| (b.distributed, b.owed))
+            .unwrap_or((0, 0));
+        *slot = Beneficiary {
+            wallet: share.wallet,
+            distributed,
+            owed,
+            share_bps: share.share_bps,
+            padding: [0; 6],
+        };
+    }
+
+    state.beneficiaries = beneficiaries;
+    state.beneficiary_count = shares.len() as u8;
+
+    emit!(BeneficiariesUpdated {
+        owner: state.owner,
+        count: state.beneficiary_count,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBeneficiaries<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// treasury PDA (pending proceeds are settled under the outgoing split)
+    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.load()?.treasury_bump)]
+    pub treasury: UncheckedAccount<'info>,
+}
@@ -0,0 +1,124 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::auction_book::AuctionBook;
+use crate::state::presale_state::{PresaleState, SaleStatus};
+use anchor_lang::prelude::*;
+
+/// Permissionless: after an auction or pro-rata sale closes, find the uniform clearing price.
+/// A pro-rata sale has every commit in bucket 0 at its fixed price, so it clears there. Walking the
+/// book from the highest bucket down, the clearing bucket is the first whose cumulative
+/// demand at its own price covers the supply. The bids above it fill in full at the
+/// clearing price, which is raised above the bucket price if they alone would oversell;
+/// the clearing (marginal) bucket shares what is left pro rata. If demand never covers the
+/// supply, every bid fills at the floor price.
+pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    let book = ctx.accounts.auction_book.load()?;
+
+    require!(state.is_commit_sale()?, PresaleError::WrongPricingMode);
+    require!(
+        !state.is_auction_settled(),
+        PresaleError::AuctionAlreadySettled
+    );
+    require!(
+        state.refresh_status(Clock::get()?.unix_timestamp)? == SaleStatus::Ended,
+        PresaleError::SaleNotEnded
+    );
+
+    let supply_raw = state.stage(1)?.cap as u128;
+    let raw_for = |state: &PresaleState, lamports: u64, price: u64| -> Result<u128> {
+        Ok(state
+            .lamports_to_micro_cents(lamports)?
+            .checked_mul(TOKEN_BASE)
+            .ok_or(PresaleError::MathOverflow)?
+            / price as u128)
+    };
+
+    let mut above: u64 = 0;
+    let mut clearing = None;
+    for bucket in (0..AUCTION_BUCKETS).rev() {
+        let price = state.auction_bucket_price(bucket)?;
+        let with_bucket = above
+            .checked_add(book.buckets[bucket])
+            .ok_or(PresaleError::MathOverflow)?;
+        if raw_for(&state, with_bucket, price)? >= supply_raw {
+            clearing = Some((bucket, price));
+            break;
+        }
+        above = with_bucket;
+    }
+
+    let (marginal_bucket, clearing_price, marginal_fill_raw, allocated_raw) = match clearing {
+        Some((bucket, bucket_price)) => {
+            // lowest price at which the bids above the marginal bucket fit in the supply
+            let fit_price: u64 = state
+                .lamports_to_micro_cents(above)?
+                .checked_mul(TOKEN_BASE)
+                .ok_or(PresaleError::MathOverflow)?
+                .div_ceil(supply_raw.max(1))
+                .try_into()
+                .map_err(|_| PresaleError::MathOverflow)?;
+            let price = bucket_price.max(fit_price);
+            let above_raw = raw_for(&state, above, price)?;
+            // the marginal bucket only fills if the price stayed at its limit
+            let marginal_raw = if price == bucket_price {
+                (supply_raw - above_raw).min(raw_for(&state, book.buckets[bucket], price)?)
+            } else {
+                0
+            };
+            (bucket, price, marginal_raw, above_raw + marginal_raw)
+        }
+        None => {
+            // undersubscribed: every bid fills in full at the floor; bucket 0 is the marginal
+            // one but its share covers all of its bids
+            let floor = state.auction_floor_price;
+            let total_raw = raw_for(&state, above, floor)?;
+            (
+                0,
+                floor,
+                raw_for(&state, book.buckets[0], floor)?,
+                total_raw,
+            )
+        }
+    };
+
+    let allocated_raw: u64 = allocated_raw
+        .try_into()
+        .map_err(|_| PresaleError::MathOverflow)?;
+    let proceeds = state
+        .cost_in_lamports(allocated_raw as u128, clearing_price)?
+        .min(state.total_committed_lamports);
+
+    state.marginal_bucket = marginal_bucket as u8;
+    state.clearing_price = clearing_price;
+    state.marginal_fill_raw = marginal_fill_raw
+        .try_into()
+        .map_err(|_| PresaleError::MathOverflow)?;
+    state.auction_settled = 1;
+    state.total_allocated = allocated_raw;
+    state.stage_mut(1)?.sold = allocated_raw;
+    // what is not proceeds goes back to bidders as they claim
+    state.refunds_owed_lamports = state
+        .total_committed_lamports
+        .checked_sub(proceeds)
+        .ok_or(PresaleError::MathOverflow)?;
+
+    emit!(AuctionSettled {
+        clearing_price,
+        marginal_bucket: state.marginal_bucket,
+        allocated_raw,
+        proceeds_lamports: proceeds,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    #[account(seeds = [AUCTION_BOOK_SEED], bump = auction_book.load()?.bump)]
+    pub auction_book: AccountLoader<'info, AuctionBook>,
+}
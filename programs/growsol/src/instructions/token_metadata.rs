@@ -0,0 +1,170 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::*;
+use crate::state::presale_state::PresaleState;
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::metadata::{
+    create_metadata_accounts_v3, update_metadata_accounts_v2, CreateMetadataAccountsV3, Metadata,
+    UpdateMetadataAccountsV2,
+};
+use anchor_spl::token_interface::Mint;
+
+fn metadata_data(name: String, symbol: String, uri: String) -> DataV2 {
+    DataV2 {
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    }
+}
+
+/// Owner: attach Metaplex metadata to the presale mint. The mint_auth PDA signs as mint
+/// authority and stays the (mutable) update authority, so the mint must not be finalized yet.
+pub fn create_token_metadata(
+    ctx: Context<CreateTokenMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let state = ctx.accounts.presale_state.load()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+
+    let mint_auth_seeds = &[MINT_SEED, &[state.mint_bump]];
+    let signer_seeds = &[&mint_auth_seeds[..]];
+
+    create_metadata_accounts_v3(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                mint_authority: ctx.accounts.mint_auth.to_account_info(),
+                payer: ctx.accounts.owner.to_account_info(),
+                update_authority: ctx.accounts.mint_auth.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        metadata_data(name.clone(), symbol.clone(), uri.clone()),
+        true,
+        true,
+        None,
+    )?;
+
+    emit!(TokenMetadataUpdated {
+        owner: state.owner,
+        mint: state.mint,
+        name,
+        symbol,
+        uri,
+    });
+
+    Ok(())
+}
+
+/// Owner: update name, symbol and URI of the presale mint's metadata.
+pub fn update_token_metadata(
+    ctx: Context<UpdateTokenMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let state = ctx.accounts.presale_state.load()?;
+    require!(
+        ctx.accounts.owner.key() == state.owner,
+        PresaleError::Unauthorized
+    );
+
+    let mint_auth_seeds = &[MINT_SEED, &[state.mint_bump]];
+    let signer_seeds = &[&mint_auth_seeds[..]];
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.mint_auth.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+        Some(metadata_data(name.clone(), symbol.clone(), uri.clone())),
+        None,
+        None,
+    )?;
+
+    emit!(TokenMetadataUpdated {
+        owner: state.owner,
+        mint: state.mint,
+        name,
+        symbol,
+        uri,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateTokenMetadata<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// mint auth PDA (mint authority and metadata update authority)
+    #[account(seeds = [MINT_SEED], bump = presale_state.load()?.mint_bump)]
+    pub mint_auth: UncheckedAccount<'info>,
+
+    /// presale mint (pinned in presale_state)
+    #[account(address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// metadata PDA, created by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
+
+    /// mint auth PDA (metadata update authority)
+    #[account(seeds = [MINT_SEED], bump = presale_state.load()?.mint_bump)]
+    pub mint_auth: UncheckedAccount<'info>,
+
+    /// presale mint (pinned in presale_state)
+    #[account(address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// metadata PDA of the presale mint
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
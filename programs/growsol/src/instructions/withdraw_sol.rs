@@ -1,40 +1,76 @@
 use crate::constants::*;
 use crate::errors::PresaleError;
 use crate::events::*;
-use crate::state::presale_state::PresaleState;
+use crate::state::presale_state::{PresaleState, SaleStatus};
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
+
+/// Lamports the treasury can pay out while staying rent exempt for its data size.
+pub fn treasury_withdrawable(treasury: &AccountInfo) -> Result<u64> {
+    let rent_exempt = Rent::get()?.minimum_balance(treasury.data_len());
+    Ok(treasury.lamports().saturating_sub(rent_exempt))
+}
+
+/// Withdrawable lamports that are sale proceeds, i.e. not owed back to auction bidders.
+pub fn treasury_proceeds(treasury: &AccountInfo, state: &PresaleState) -> Result<u64> {
+    Ok(treasury_withdrawable(treasury)?.saturating_sub(state.refunds_owed_lamports))
+}
 
 pub fn withdraw_sol(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
-    let state = &ctx.accounts.presale_state;
+    // ensure treasury has enough lamports and stays rent exempt afterwards
+    let treasury = ctx.accounts.treasury.to_account_info();
+    require!(treasury.lamports() >= amount, PresaleError::InsufficientFunds);
+    require!(
+        amount <= treasury_withdrawable(&treasury)?,
+        PresaleError::TreasuryRentExemption
+    );
+    require!(
+        amount <= treasury_proceeds(&treasury, &*ctx.accounts.presale_state.load()?)?,
+        PresaleError::InsufficientFunds
+    );
+
+    pay_out(ctx, amount)
+}
+
+pub fn withdraw_all_sol(ctx: Context<WithdrawSol>) -> Result<()> {
+    let amount = treasury_proceeds(
+        &ctx.accounts.treasury.to_account_info(),
+        &*ctx.accounts.presale_state.load()?,
+    )?;
+    require!(amount > 0, PresaleError::InsufficientFunds);
+
+    pay_out(ctx, amount)
+}
+
+fn pay_out(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
+    let state = ctx.accounts.presale_state.load()?;
 
     require!(
         ctx.accounts.owner.key() == state.owner,
         PresaleError::Unauthorized
     );
+    // a cancelled sale's treasury belongs to the buyers awaiting refunds
+    require!(
+        state.status()? != SaleStatus::Cancelled,
+        PresaleError::SaleCancelled
+    );
+    // with a revenue split configured every lamport goes through distribute_treasury
+    require!(
+        state.beneficiary_count == 0,
+        PresaleError::RevenueSplitActive
+    );
+    require!(
+        state.is_payout_destination(&ctx.accounts.recipient.key()),
+        PresaleError::DestinationNotAllowed
+    );
 
-    // ensure treasury has enough lamports
-    let treasury_lamports = **ctx.accounts.treasury.to_account_info().lamports.borrow();
-    require!(treasury_lamports >= amount, PresaleError::InsufficientFunds);
-
-    // use PDA signer seeds for treasury PDA
-    let treasury_seeds = &[TREASURY_SEED.as_ref(), &[state.treasury_bump]];
-    let signer_seeds = &[&treasury_seeds[..]];
-
-    system_program::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.treasury.to_account_info(),
-                to: ctx.accounts.owner.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        amount,
-    )?;
+    // treasury is program-owned and carries data, so lamports are moved directly
+    // rather than through system_program::transfer
+    ctx.accounts.treasury.sub_lamports(amount)?;
+    ctx.accounts.recipient.add_lamports(amount)?;
 
     emit!(WithdrawnSol {
         owner: state.owner,
+        recipient: ctx.accounts.recipient.key(),
         amount,
     });
 
@@ -46,12 +82,16 @@ pub struct WithdrawSol<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.bump)]
-    pub presale_state: Account<'info, PresaleState>,
+    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
 
-    /// treasury PDA (signer via seeds)
-    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.treasury_bump)]
+    /// treasury PDA (program-owned, debited directly)
+    #[account(mut, seeds = [TREASURY_SEED], bump = presale_state.load()?.treasury_bump)]
     pub treasury: UncheckedAccount<'info>,
 
+    /// recipient wallet (owner or an allowlisted payout destination)
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
@@ -1,27 +1,62 @@
 use crate::constants::*;
 use crate::errors::PresaleError;
 use crate::events::*;
-use crate::state::presale_state::PresaleState;
+use crate::state::presale_state::{DistributionMode, PresaleState};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, TokenAccount, Token};
+use crate::utils::transfer_fee;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 pub fn withdraw_token(ctx: Context<WithdrawToken>, amount_raw: u64) -> Result<()> {
-    let state = &ctx.accounts.presale_state;
+    let mut state = ctx.accounts.presale_state.load_mut()?;
+    let owner = state.owner;
+    let bump = state.bump;
 
     require!(
         ctx.accounts.owner.key() == state.owner,
         PresaleError::Unauthorized
     );
+    require!(
+        state.is_payout_destination(&ctx.accounts.recipient.key()),
+        PresaleError::DestinationNotAllowed
+    );
+
+    // tokens backing unclaimed allocations stay in the vault, and so does a pre-funded
+    // sale's whole inventory until the sale has ended
+    let pre_funded = state.distribution_mode()? == DistributionMode::PreFundedVault;
+    let backing_claims = if pre_funded && state.is_open(Clock::get()?.unix_timestamp)? {
+        // what was already claimed mid-sale has left the vault
+        state.required_inventory()?.saturating_sub(state.total_claimed)
+    } else if state.claims_from_vault()? {
+        state.unclaimed_raw()?
+    } else {
+        0
+    };
+    if pre_funded {
+        state.total_deposited = state.total_deposited.saturating_sub(amount_raw);
+    }
+    // presale_state signs below, so its data must not stay borrowed
+    drop(state);
+    require!(
+        ctx.accounts
+            .presale_token_account
+            .amount
+            .saturating_sub(backing_claims)
+            >= amount_raw,
+        PresaleError::InsufficientVaultBalance
+    );
 
-    // use mint_auth PDA as authority signer to move tokens from presale_token_account
-    let mint_auth_seeds = &[MINT_SEED.as_ref(), &[state.mint_bump]];
-    let signer_seeds = &[&mint_auth_seeds[..]];
+    // the vault is the ATA of presale_state, so presale_state signs the transfer
+    let presale_state_seeds = &[PRESALE_STATE_SEED, &[bump]];
+    let signer_seeds = &[&presale_state_seeds[..]];
 
-    let cpi_accounts = token::Transfer {
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.presale_token_account.to_account_info(),
-        to: ctx.accounts.owner_token_account.to_account_info(),
-        authority: ctx.accounts.mint_auth.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
+        authority: ctx.accounts.presale_state.to_account_info(),
     };
 
     let cpi_ctx = CpiContext::new_with_signer(
@@ -30,11 +65,14 @@ pub fn withdraw_token(ctx: Context<WithdrawToken>, amount_raw: u64) -> Result<()
         signer_seeds,
     );
 
-    token::transfer(cpi_ctx, amount_raw)?;
+    let fee_raw = transfer_fee(&ctx.accounts.mint, amount_raw)?;
+    token_interface::transfer_checked(cpi_ctx, amount_raw, ctx.accounts.mint.decimals)?;
 
     emit!(WithdrawnToken {
-        owner: state.owner,
+        owner,
+        recipient: ctx.accounts.recipient.key(),
         amount_raw,
+        fee_raw,
     });
 
     Ok(())
@@ -45,28 +83,40 @@ pub struct WithdrawToken<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    #[account(seeds = [PRESALE_STATE_SEED], bump = presale_state.bump)]
-    pub presale_state: Account<'info, PresaleState>,
+    #[account(mut, seeds = [PRESALE_STATE_SEED], bump = presale_state.load()?.bump)]
+    pub presale_state: AccountLoader<'info, PresaleState>,
 
-    #[account(mut, seeds = [MINT_SEED], bump = presale_state.mint_bump)]
+    /// mint auth PDA (must match presale_state.mint_bump)
+    #[account(seeds = [MINT_SEED], bump = presale_state.load()?.mint_bump)]
     pub mint_auth: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    /// presale mint (pinned in presale_state)
+    #[account(address = presale_state.load()?.mint @ PresaleError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// recipient wallet (owner or an allowlisted payout destination)
+    pub recipient: UncheckedAccount<'info>,
 
-    /// owner's ATA (will be created if needed)
+    /// recipient's ATA (will be created if needed, paid by owner)
     #[account(
         init_if_needed,
         payer = owner,
         associated_token::mint = mint,
-        associated_token::authority = owner,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
     )]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
-    pub presale_token_account: Account<'info, TokenAccount>,
+    /// presale vault: the ATA of presale_state for the presale mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = presale_state,
+        associated_token::token_program = token_program,
+    )]
+    pub presale_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -6,8 +6,10 @@ pub mod state;
 pub mod constants;
 pub mod events;
 pub mod instructions;
+pub mod utils;
 
 use instructions::*;
+use state::presale_state::{BondingCurve, ExpiredStagePolicy, UnsoldAction, VolumeBasis, VolumeTier};
 
 declare_id!("DjWmjS3imyiNpBVzv7LFFVZWztcYjAAXpXE2RM61oAGc");
 
@@ -15,14 +17,48 @@ declare_id!("DjWmjS3imyiNpBVzv7LFFVZWztcYjAAXpXE2RM61oAGc");
 pub mod growsol {
     use super::*;
 
-    /// Initialize the presale (owner creates presale state, mint, PDAs and presale ATA).
+    /// Initialize the presale (owner creates presale state, mint, PDAs and presale ATA),
+    /// optionally pricing on a linear bonding curve instead of the stage ladder.
     pub fn initialize(
         ctx: Context<Initialize>,
         usd_per_sol: u64,
         presale_start_ts: i64,
         presale_end_ts: i64,
+        max_supply: u64,
+        reserved_supply: u64,
+        curve: Option<BondingCurve>,
     ) -> Result<()> {
-        instructions::initialize(ctx, usd_per_sol, presale_start_ts, presale_end_ts)
+        instructions::initialize(
+            ctx,
+            usd_per_sol,
+            presale_start_ts,
+            presale_end_ts,
+            max_supply,
+            reserved_supply,
+            curve,
+        )
+    }
+
+    /// Initialize a pre-funded presale for an existing mint (claims transfer from the vault).
+    pub fn initialize_with_mint(
+        ctx: Context<InitializeWithMint>,
+        usd_per_sol: u64,
+        presale_start_ts: i64,
+        presale_end_ts: i64,
+        curve: Option<BondingCurve>,
+    ) -> Result<()> {
+        instructions::initialize_with_mint(
+            ctx,
+            usd_per_sol,
+            presale_start_ts,
+            presale_end_ts,
+            curve,
+        )
+    }
+
+    /// Admin: deposit sale inventory into the presale vault (pre-funded mode).
+    pub fn deposit_tokens(ctx: Context<DepositTokens>, amount_raw: u64) -> Result<()> {
+        instructions::deposit_tokens(ctx, amount_raw)
     }
 
     /// Buyer sends lamports (SOL) and receives token allocation (no immediate mint).
@@ -30,17 +66,23 @@ pub mod growsol {
         instructions::buy_tokens(ctx, sol_amount)
     }
 
-    /// Claim function — mints any unclaimed allocated tokens into the buyer's ATA.
-    pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
-        instructions::claim_tokens(ctx)
+    /// Claim function — mints any unclaimed allocated tokens into the buyer's ATA,
+    /// optionally closing the fully claimed allocation to reclaim its rent.
+    pub fn claim_tokens(ctx: Context<ClaimTokens>, close_when_done: bool) -> Result<()> {
+        instructions::claim_tokens(ctx, close_when_done)
     }
 
-    /// Owner withdraw SOL from the treasury PDA to owner's wallet via system_program::transfer with PDA signer.
+    /// Owner withdraw SOL from the treasury PDA to the owner or an allowlisted payout destination.
     pub fn withdraw_sol(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
         instructions::withdraw_sol(ctx, amount)
     }
 
-    /// Owner withdraw tokens from presale vault to the owner's ATA.
+    /// Owner withdraw the whole treasury balance above its rent-exempt minimum.
+    pub fn withdraw_all_sol(ctx: Context<WithdrawSol>) -> Result<()> {
+        instructions::withdraw_all_sol(ctx)
+    }
+
+    /// Owner withdraw tokens from presale vault to the ATA of the owner or an allowlisted payout destination.
     pub fn withdraw_token(ctx: Context<WithdrawToken>, amount_raw: u64) -> Result<()> {
         instructions::withdraw_token(ctx, amount_raw)
     }
@@ -61,4 +103,227 @@ pub mod growsol {
     pub fn resume_sale(ctx: Context<AdminToggleSale>) -> Result<()> {
         instructions::pause_sale::resume_sale(ctx)
     }
-}
\ No newline at end of file
+
+    /// Admin: allowlist a wallet as a withdrawal recipient
+    pub fn add_payout_destination(
+        ctx: Context<AdminPayoutDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::add_payout_destination(ctx, destination)
+    }
+
+    /// Admin: remove a wallet from the withdrawal allowlist
+    pub fn remove_payout_destination(
+        ctx: Context<AdminPayoutDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_payout_destination(ctx, destination)
+    }
+
+    /// Admin: configure the revenue split (wallets and basis-point shares); pending proceeds
+    /// are first paid out under the old split
+    pub fn set_beneficiaries<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetBeneficiaries<'info>>,
+        shares: Vec<BeneficiaryShare>,
+    ) -> Result<()> {
+        instructions::set_beneficiaries(ctx, shares)
+    }
+
+    /// Permissionless: pay each beneficiary its share of the treasury balance above rent;
+    /// a wallet that cannot take its payment yet has it held back for a later call.
+    pub fn distribute_treasury<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeTreasury<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_treasury(ctx)
+    }
+
+    /// View: presale mint, vault and treasury addresses (returned via return data).
+    pub fn get_presale_info(ctx: Context<GetPresaleInfo>) -> Result<PresaleInfo> {
+        instructions::get_presale_info(ctx)
+    }
+
+    /// Admin: close the sale after presale_end or sell-out and lock its configuration.
+    pub fn finalize(ctx: Context<FinalizeSale>) -> Result<()> {
+        instructions::finalize(ctx)
+    }
+
+    /// Admin: after finalize, mint outstanding allocations and the reserve, then revoke mint authority.
+    pub fn finalize_mint(ctx: Context<FinalizeMint>) -> Result<()> {
+        instructions::finalize_mint(ctx)
+    }
+
+    /// Admin: after finalize, burn unsold inventory or send it to a reserve / liquidity wallet.
+    pub fn finalize_unsold(ctx: Context<FinalizeUnsold>, action: UnsoldAction) -> Result<()> {
+        instructions::finalize_unsold(ctx, action)
+    }
+
+    /// Admin: create Metaplex metadata (name, symbol, URI) for the presale mint.
+    pub fn create_token_metadata(
+        ctx: Context<CreateTokenMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::create_token_metadata(ctx, name, symbol, uri)
+    }
+
+    /// Admin: update the presale mint's metadata.
+    pub fn update_token_metadata(
+        ctx: Context<UpdateTokenMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        instructions::update_token_metadata(ctx, name, symbol, uri)
+    }
+
+    /// Admin: cancel the sale (blocks withdrawals and claims, enables refunds).
+    pub fn cancel_sale(ctx: Context<CancelSale>) -> Result<()> {
+        instructions::cancel_sale(ctx)
+    }
+
+    /// Buyer: refund the lamports paid into a cancelled sale and close the allocation.
+    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        instructions::refund(ctx)
+    }
+
+    /// Buyer: close a fully claimed allocation and reclaim its rent.
+    pub fn close_allocation(ctx: Context<CloseAllocation>) -> Result<()> {
+        instructions::close_allocation(ctx)
+    }
+
+    /// Admin: grow the presale state to the current layout and upgrade it in place.
+    pub fn migrate_presale_state(ctx: Context<MigratePresaleState>) -> Result<()> {
+        instructions::migrate_presale_state(ctx)
+    }
+
+    /// Buyer: grow an allocation to the current layout and upgrade it in place.
+    pub fn migrate_user_allocation(ctx: Context<MigrateUserAllocation>) -> Result<()> {
+        instructions::migrate_user_allocation(ctx)
+    }
+
+    /// Admin: schedule start times for stages 2..=stage_count and the expired-stage capacity policy.
+    pub fn admin_set_stage_schedule(
+        ctx: Context<AdminSetStageSchedule>,
+        starts: Vec<i64>,
+        policy: ExpiredStagePolicy,
+    ) -> Result<()> {
+        instructions::admin_set_stage_schedule(ctx, starts, policy)
+    }
+
+    /// Admin: set price (cents) and cap (raw) of a stage.
+    pub fn admin_set_stage(
+        ctx: Context<AdminStage>,
+        stage: u8,
+        price: u64,
+        cap: u64,
+    ) -> Result<()> {
+        instructions::admin_set_stage(ctx, stage, price, cap)
+    }
+
+    /// Admin: insert a stage at `stage`, shifting later stages back.
+    pub fn admin_insert_stage(
+        ctx: Context<AdminStage>,
+        stage: u8,
+        price: u64,
+        cap: u64,
+    ) -> Result<()> {
+        instructions::admin_insert_stage(ctx, stage, price, cap)
+    }
+
+    /// Admin: remove a stage, shifting later stages forward.
+    pub fn admin_remove_stage(ctx: Context<AdminStage>, stage: u8) -> Result<()> {
+        instructions::admin_remove_stage(ctx, stage)
+    }
+
+    /// Admin: turn a sale that has not started into a uniform-price auction.
+    pub fn configure_auction(
+        ctx: Context<ConfigureAuction>,
+        floor_price: u64,
+        tick: u64,
+        supply: u64,
+    ) -> Result<()> {
+        instructions::configure_auction(ctx, floor_price, tick, supply)
+    }
+
+    /// Admin: turn a sale that has not started into a pro-rata fair launch at a fixed price.
+    pub fn configure_pro_rata(
+        ctx: Context<ConfigureAuction>,
+        price: u64,
+        supply: u64,
+    ) -> Result<()> {
+        instructions::configure_pro_rata(ctx, price, supply)
+    }
+
+    /// Bidder commits SOL to an auction (optional limit price) or a pro-rata sale.
+    pub fn commit_bid(
+        ctx: Context<CommitBid>,
+        sol_amount: u64,
+        limit_price: Option<u64>,
+    ) -> Result<()> {
+        instructions::commit_bid(ctx, sol_amount, limit_price)
+    }
+
+    /// Permissionless: settle an auction or pro-rata sale after it closes.
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        instructions::settle_auction(ctx)
+    }
+
+    /// Admin: configure the anti-sniping sale extension (zero threshold = off).
+    pub fn admin_set_extension_rule(
+        ctx: Context<AdminSetExtensionRule>,
+        threshold_lamports: u64,
+        window: u32,
+        step: u32,
+        max_extension: u32,
+    ) -> Result<()> {
+        instructions::admin_set_extension_rule(ctx, threshold_lamports, window, step, max_extension)
+    }
+
+    /// Admin: set the per-wallet purchase cooldown and the per-slot allocation ceiling.
+    pub fn admin_set_rate_limits(
+        ctx: Context<AdminSetRateLimits>,
+        purchase_cooldown: u32,
+        slot_cap_raw: u64,
+    ) -> Result<()> {
+        instructions::admin_set_rate_limits(ctx, purchase_cooldown, slot_cap_raw)
+    }
+
+    /// Admin: block a wallet from buying and claiming.
+    pub fn admin_denylist_add(ctx: Context<AdminDenylistAdd>, wallet: Pubkey) -> Result<()> {
+        instructions::admin_denylist_add(ctx, wallet)
+    }
+
+    /// Admin: lift the block on a wallet.
+    pub fn admin_denylist_remove(ctx: Context<AdminDenylistRemove>, wallet: Pubkey) -> Result<()> {
+        instructions::admin_denylist_remove(ctx, wallet)
+    }
+
+    /// Admin: hold or release a buyer's claims.
+    pub fn admin_set_allocation_frozen(
+        ctx: Context<AdminSetAllocationFrozen>,
+        wallet: Pubkey,
+        frozen: bool,
+    ) -> Result<()> {
+        instructions::admin_set_allocation_frozen(ctx, wallet, frozen)
+    }
+
+    /// Admin: configure early-bird and volume bonus tiers and the bonus pool cap.
+    pub fn admin_set_bonus(
+        ctx: Context<AdminSetBonus>,
+        early_bird_bps: u16,
+        early_bird_duration: u32,
+        volume_tiers: Vec<VolumeTier>,
+        volume_basis: VolumeBasis,
+        pool_cap: u64,
+    ) -> Result<()> {
+        instructions::admin_set_bonus(
+            ctx,
+            early_bird_bps,
+            early_bird_duration,
+            volume_tiers,
+            volume_basis,
+            pool_cap,
+        )
+    }
+}
@@ -0,0 +1,15 @@
+use crate::constants::*;
+use anchor_lang::prelude::*;
+
+/// Committed lamports per auction price bucket, created by `configure_auction`. Bucket `i`
+/// holds the bids limited to `auction_floor_price + i * auction_tick`.
+#[account(zero_copy)]
+pub struct AuctionBook {
+    pub buckets: [u64; AUCTION_BUCKETS],
+    pub bump: u8,
+    pub padding: [u8; 7],
+}
+
+impl AuctionBook {
+    pub const LEN: usize = 8 + std::mem::size_of::<AuctionBook>();
+}
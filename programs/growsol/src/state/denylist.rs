@@ -0,0 +1,26 @@
+use crate::errors::PresaleError;
+use anchor_lang::prelude::*;
+
+/// Marker PDA blocking one wallet from buying and claiming; it exists only while the wallet
+/// is denylisted. seeds = ["denylisted", wallet]
+#[account]
+#[derive(InitSpace)]
+pub struct Denylisted {
+    pub wallet: Pubkey,
+    pub bump: u8,
+}
+
+impl Denylisted {
+    /// Reject a denylisted wallet. `entry` is the wallet's marker address (seeds checked by
+    /// the caller); it may only be left out while nobody is denylisted.
+    pub fn check(entry: Option<&UncheckedAccount>, denylist_count: u32) -> Result<()> {
+        match entry {
+            Some(entry) => require!(
+                entry.owner != &crate::ID || entry.data_is_empty(),
+                PresaleError::WalletDenylisted
+            ),
+            None => require!(denylist_count == 0, PresaleError::DenylistEntryRequired),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+//! Layout `PresaleState` had before the program versioned its accounts, kept only so
+//! `migrate_presale_state` can read accounts written by it.
+
+use crate::state::presale_state::*;
+use anchor_lang::prelude::*;
+
+// migrate_presale_state tells the layouts apart by account size
+const _: () = assert!(PresaleStateV0::LEN != PresaleState::LEN);
+
+/// `PresaleState` version 0: Borsh, a fixed five-stage ladder and no mint, supply or
+/// lifecycle fields. The mint was created at `initialize` with `mint_auth` as its authority
+/// and claims minted straight to the buyer.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct PresaleStateV0 {
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub mint_bump: u8,
+    pub treasury_bump: u8,
+
+    pub usd_per_sol: u64,
+    pub presale_start: i64,
+    pub presale_end: i64,
+    pub paused: bool,
+    pub current_stage: u8,
+
+    // prices (cents)
+    pub stage_1_price: u64,
+    pub stage_2_price: u64,
+    pub stage_3_price: u64,
+    pub stage_4_price: u64,
+    pub stage_5_price: u64,
+
+    // caps (raw)
+    pub stage_1_cap: u64,
+    pub stage_2_cap: u64,
+    pub stage_3_cap: u64,
+    pub stage_4_cap: u64,
+    pub stage_5_cap: u64,
+
+    // sold counters (raw)
+    pub stage_1_sold: u64,
+    pub stage_2_sold: u64,
+    pub stage_3_sold: u64,
+    pub stage_4_sold: u64,
+    pub stage_5_sold: u64,
+
+    pub total_allocated: u64,
+}
+
+impl PresaleStateV0 {
+    pub const LEN: usize = 8 + PresaleStateV0::INIT_SPACE;
+
+    /// Copy every field into the current layout; the five stages become the first five
+    /// ladder entries. v0 did not record its mint, so `mint` / `token_program` come from the
+    /// caller and `minted_supply` (the mint's supply) stands in for both minted and claimed
+    /// totals, since v0 only ever minted on claim. The hard cap is the ladder itself: no
+    /// reserve, no bonus pool.
+    pub fn write_to(
+        &self,
+        state: &mut PresaleState,
+        mint: Pubkey,
+        token_program: Pubkey,
+        minted_supply: u64,
+    ) -> Result<()> {
+        state.owner = self.owner;
+        state.mint = mint;
+        state.token_program = token_program;
+        state.bump = self.bump;
+        state.mint_bump = self.mint_bump;
+        state.treasury_bump = self.treasury_bump;
+
+        state.usd_per_sol = self.usd_per_sol;
+        state.presale_start = self.presale_start;
+        state.presale_end = self.presale_end;
+        state.paused = u8::from(self.paused);
+        state.current_stage = self.current_stage;
+
+        let stages = [
+            (self.stage_1_price, self.stage_1_cap, self.stage_1_sold),
+            (self.stage_2_price, self.stage_2_cap, self.stage_2_sold),
+            (self.stage_3_price, self.stage_3_cap, self.stage_3_sold),
+            (self.stage_4_price, self.stage_4_cap, self.stage_4_sold),
+            (self.stage_5_price, self.stage_5_cap, self.stage_5_sold),
+        ];
+        for (slot, (price, cap, sold)) in state.stages.iter_mut().zip(stages) {
+            *slot = Stage {
+                price,
+                cap,
+                sold,
+                start: 0,
+            };
+        }
+        state.stage_count = stages.len() as u8;
+        state.total_allocated = self.total_allocated;
+
+        state.max_supply = state.sale_supply()?;
+        state.total_minted = minted_supply;
+        state.total_claimed = minted_supply;
+
+        state.distribution_mode = DistributionMode::MintOnClaim as u8;
+        state.pricing_mode = PricingMode::Ladder as u8;
+        state.expired_stage_policy = ExpiredStagePolicy::Discard as u8;
+        state.set_status(SaleStatus::Pending);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+    use bytemuck::Zeroable;
+
+    /// A v0 account as the pre-versioning `initialize` / `buy_tokens` wrote it: 8-byte
+    /// discriminator, then the fields in declaration order (197 bytes in total).
+    fn baseline_account(owner: Pubkey) -> Vec<u8> {
+        let mut data = vec![0xAA; 8];
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&[254, 253, 252]);
+        data.extend_from_slice(&150u64.to_le_bytes());
+        data.extend_from_slice(&1_000i64.to_le_bytes());
+        data.extend_from_slice(&2_000i64.to_le_bytes());
+        data.extend_from_slice(&[1, 2]);
+        for price in 1..=5u64 {
+            data.extend_from_slice(&price.to_le_bytes());
+        }
+        for tokens in [
+            STAGE_1_TOKENS,
+            STAGE_2_TOKENS,
+            STAGE_3_TOKENS,
+            STAGE_4_TOKENS,
+            STAGE_5_TOKENS,
+        ] {
+            data.extend_from_slice(&((tokens * TOKEN_BASE) as u64).to_le_bytes());
+        }
+        let stage_1_cap = (STAGE_1_TOKENS * TOKEN_BASE) as u64;
+        for sold in [stage_1_cap, 7, 0, 0, 0] {
+            data.extend_from_slice(&sold.to_le_bytes());
+        }
+        data.extend_from_slice(&(stage_1_cap + 7).to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn baseline_account_migrates_to_the_current_layout() {
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+        let data = baseline_account(owner);
+        assert_eq!(data.len(), PresaleStateV0::LEN);
+
+        let legacy = PresaleStateV0::deserialize(&mut &data[8..]).unwrap();
+        let mut state = PresaleState::zeroed();
+        legacy
+            .write_to(&mut state, mint, token_program, 42)
+            .unwrap();
+
+        assert_eq!(state.owner, owner);
+        assert_eq!((state.mint, state.token_program), (mint, token_program));
+        assert_eq!(
+            (state.bump, state.mint_bump, state.treasury_bump),
+            (254, 253, 252)
+        );
+        assert_eq!(state.usd_per_sol, 150);
+        assert_eq!((state.presale_start, state.presale_end), (1_000, 2_000));
+        assert!(state.is_paused());
+        assert_eq!(state.current_stage, 2);
+
+        let stage_1_cap = (STAGE_1_TOKENS * TOKEN_BASE) as u64;
+        assert_eq!(state.stage_count, 5);
+        assert_eq!(state.stage(1).unwrap().sold, stage_1_cap);
+        assert_eq!(state.stage(2).unwrap().sold, 7);
+        assert_eq!(state.stage(5).unwrap().price, 5);
+        assert_eq!(state.total_allocated, stage_1_cap + 7);
+
+        assert_eq!(state.max_supply, state.sale_supply().unwrap());
+        assert_eq!((state.total_minted, state.total_claimed), (42, 42));
+        assert_eq!(state.unclaimed_raw().unwrap(), stage_1_cap + 7 - 42);
+        state.check_supply().unwrap();
+        assert!(state.distribution_mode().unwrap() == DistributionMode::MintOnClaim);
+        assert_eq!(state.pricing_mode().unwrap(), PricingMode::Ladder);
+        assert_eq!(state.effective_status(1_500).unwrap(), SaleStatus::Live);
+    }
+}
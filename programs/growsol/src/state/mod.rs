@@ -1,5 +1,10 @@
+pub mod auction_book;
+pub mod denylist;
+pub mod legacy;
 pub mod presale_state;
 pub mod user_state;
 
+pub use auction_book::*;
+pub use denylist::*;
 pub use presale_state::*;
-pub use user_state::*;
\ No newline at end of file
+pub use user_state::*;
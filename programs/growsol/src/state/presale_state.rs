@@ -1,38 +1,1120 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
+use crate::events::{SaleExtended, SaleStatusChanged, StageExpired};
+use crate::state::AuctionBook;
+use crate::utils::isqrt;
+use std::cmp::Ordering;
 use anchor_lang::prelude::*;
 
-#[account]
+/// Zero-copy sale state. Fields are grouped by alignment (32-byte keys, then u64/i64,
+/// then u8) so the `repr(C)` layout has no implicit padding; enums and flags are stored
+/// as `u8` and read through the accessors below.
+#[account(zero_copy)]
 pub struct PresaleState {
     pub owner: Pubkey,
-    pub bump: u8,
-    pub mint_bump: u8,
-    pub treasury_bump: u8,
+    /// sale token mint created at `initialize`
+    pub mint: Pubkey,
+    /// token program owning the mint (classic SPL Token or Token-2022)
+    pub token_program: Pubkey,
+
+    // withdrawal recipients allowlisted by the owner (first `payout_destination_count` are valid)
+    pub payout_destinations: [Pubkey; MAX_PAYOUT_DESTINATIONS],
+    pub unsold_destination: Pubkey,
+
+    // revenue split (first `beneficiary_count` are valid, shares sum to BPS_DENOMINATOR)
+    pub beneficiaries: [Beneficiary; MAX_BENEFICIARIES],
+
+    // price ladder (first `stage_count` are valid); stage numbers are 1-based, so stage n
+    // lives at `stages[n - 1]`
+    pub stages: [Stage; MAX_STAGES],
 
     pub usd_per_sol: u64,
     pub presale_start: i64,
     pub presale_end: i64,
-    pub paused: bool,
+
+    pub total_allocated: u64,
+    pub total_distributed: u64,
+
+    // supply (raw): hard cap, reserve minted at finalize_mint, running counters
+    pub max_supply: u64,
+    pub reserved_supply: u64,
+    pub total_minted: u64,
+    pub total_claimed: u64,
+    /// inventory deposited for PreFundedVault sales, net of withdrawals (raw)
+    pub total_deposited: u64,
+
+    // unsold inventory decision recorded by finalize_unsold (raw)
+    pub unsold_amount: u64,
+    pub liquidity_tranche: u64,
+
+    pub finalized_at: i64,
+    pub total_raised_lamports: u64,
+    pub total_refunded_lamports: u64,
+
+    pub bump: u8,
+    pub mint_bump: u8,
+    pub treasury_bump: u8,
+    /// 0 / 1
+    pub paused: u8,
     pub current_stage: u8,
+    pub stage_count: u8,
+    pub payout_destination_count: u8,
+    pub beneficiary_count: u8,
+    /// 0 / 1; set once finalize_mint revoked the mint authority; claims are then paid from the vault
+    pub mint_finalized: u8,
+    /// `DistributionMode`: how claims are delivered
+    pub distribution_mode: u8,
+    /// 0 = not decided yet, otherwise `UnsoldAction` + 1
+    pub unsold_action: u8,
+    /// `SaleStatus`: Finalized / Cancelled are terminal, the rest follow the clock and sell-out
+    pub status: u8,
+    /// `ExpiredStagePolicy`: what happens to unsold capacity of a stage whose time ran out
+    pub expired_stage_policy: u8,
+    /// layout version; new fields are carved out of `reserved`
+    pub version: u8,
+    /// `PricingMode`: step ladder or bonding curve
+    pub pricing_mode: u8,
+    pub padding: [u8; 1],
 
-    // prices (cents)
-    pub stage_1_price: u64,
-    pub stage_2_price: u64,
-    pub stage_3_price: u64,
-    pub stage_4_price: u64,
-    pub stage_5_price: u64,
-
-    // caps (raw)
-    pub stage_1_cap: u64,
-    pub stage_2_cap: u64,
-    pub stage_3_cap: u64,
-    pub stage_4_cap: u64,
-    pub stage_5_cap: u64,
-
-    // sold counters (raw)
-    pub stage_1_sold: u64,
-    pub stage_2_sold: u64,
-    pub stage_3_sold: u64,
-    pub stage_4_sold: u64,
-    pub stage_5_sold: u64,
+    // linear bonding curve (micro-cents per whole token) across stage 1's cap
+    pub curve_start_price: u64,
+    pub curve_end_price: u64,
 
-    pub total_allocated: u64,
-}
\ No newline at end of file
+    // uniform-price auction (micro-cents per whole token): bucket i bids at
+    // `auction_floor_price + i * auction_tick`; the rest is set by settle_auction
+    pub auction_floor_price: u64,
+    pub auction_tick: u64,
+    pub clearing_price: u64,
+    /// raw units shared pro rata by the bids in `marginal_bucket`
+    pub marginal_fill_raw: u64,
+    pub total_committed_lamports: u64,
+    /// committed lamports the treasury still owes back to bidders; never withdrawable
+    pub refunds_owed_lamports: u64,
+    /// bids above this bucket fill in full, bids below it not at all
+    pub marginal_bucket: u8,
+    /// 0 / 1
+    pub auction_settled: u8,
+    pub auction_padding: [u8; 6],
+
+    // anti-sniping: a purchase above `extension_threshold_lamports` (0 = off) within
+    // `extension_window` seconds of the end pushes presale_end back by `extension_step`
+    // seconds, up to `max_extension` seconds in total
+    pub extension_threshold_lamports: u64,
+    pub extension_window: u32,
+    pub extension_step: u32,
+    pub max_extension: u32,
+    /// seconds presale_end has been pushed back so far
+    pub total_extension: u32,
+
+    // rate limits: raw units buy_tokens may allocate per slot (0 = unlimited) and the
+    // running total for `rate_limit_slot`; seconds a wallet waits between purchases
+    pub slot_cap_raw: u64,
+    pub rate_limit_slot: u64,
+    pub slot_allocated_raw: u64,
+    pub purchase_cooldown: u32,
+    /// live `Denylisted` markers; while non-zero buyers must pass their denylist entry
+    pub denylist_count: u32,
+
+    // bonus tokens on top of the base allocation, paid from a pool of `bonus_pool_cap` raw
+    // units: `early_bird_bps` for `early_bird_duration` seconds after presale_start, plus
+    // the highest volume tier reached (first `bonus_tier_count` are valid)
+    pub bonus_pool_cap: u64,
+    pub bonus_allocated: u64,
+    pub bonus_tiers: [BonusTier; MAX_BONUS_TIERS],
+    pub early_bird_duration: u32,
+    pub early_bird_bps: u16,
+    /// `VolumeBasis`: tiers count a single purchase or the buyer's running total
+    pub volume_basis: u8,
+    pub bonus_tier_count: u8,
+
+    pub reserved: [u8; PRESALE_RESERVED_BYTES],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SaleStatus {
+    Pending,
+    Live,
+    Ended,
+    Finalized,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionMode {
+    /// program owns the mint authority and mints on claim
+    MintOnClaim,
+    /// existing fixed-supply mint; the owner deposits inventory and claims transfer from the vault
+    PreFundedVault,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq)]
+pub enum UnsoldAction {
+    /// burn vault inventory, or lower max_supply so it can never be minted
+    Burn,
+    /// deliver to an allowlisted reserve wallet
+    SendToReserve,
+    /// deliver to an allowlisted liquidity wallet and track it as the liquidity tranche
+    RollToLiquidity,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExpiredStagePolicy {
+    /// unsold capacity of an expired stage is dropped from the ladder
+    Discard,
+    /// unsold capacity of an expired stage is added to the next stage's cap
+    RollForward,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PricingMode {
+    /// fixed price per stage
+    Ladder,
+    /// price rises linearly with tokens sold
+    LinearCurve,
+    /// bids commit SOL with a limit price and settle at one uniform clearing price
+    Auction,
+    /// buyers commit SOL at a fixed price; an oversubscribed supply is shared pro rata
+    ProRata,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VolumeBasis {
+    /// the purchase alone is measured against the tiers
+    PerPurchase,
+    /// everything the buyer has spent so far, this purchase included
+    Cumulative,
+}
+
+impl VolumeBasis {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::PerPurchase,
+            1 => Self::Cumulative,
+            _ => return err!(ErrorCode::AccountDidNotDeserialize),
+        })
+    }
+}
+
+/// Volume bonus tier accepted by `admin_set_bonus`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct VolumeTier {
+    pub min_usd_cents: u64,
+    pub bps: u16,
+}
+
+/// Bonding-curve parameters accepted at initialize: the price moves from `start_price` to
+/// `end_price` (micro-cents per whole token) as `supply` whole tokens sell.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BondingCurve {
+    pub start_price: u64,
+    pub end_price: u64,
+    pub supply: u64,
+}
+
+impl PricingMode {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Ladder,
+            1 => Self::LinearCurve,
+            2 => Self::Auction,
+            3 => Self::ProRata,
+            _ => return err!(ErrorCode::AccountDidNotDeserialize),
+        })
+    }
+}
+
+impl ExpiredStagePolicy {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Discard,
+            1 => Self::RollForward,
+            _ => return err!(ErrorCode::AccountDidNotDeserialize),
+        })
+    }
+}
+
+impl SaleStatus {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Pending,
+            1 => Self::Live,
+            2 => Self::Ended,
+            3 => Self::Finalized,
+            4 => Self::Cancelled,
+            _ => return err!(ErrorCode::AccountDidNotDeserialize),
+        })
+    }
+}
+
+impl DistributionMode {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::MintOnClaim,
+            1 => Self::PreFundedVault,
+            _ => return err!(ErrorCode::AccountDidNotDeserialize),
+        })
+    }
+}
+
+impl UnsoldAction {
+    pub fn from_u8(value: u8) -> Result<Self> {
+        Ok(match value {
+            0 => Self::Burn,
+            1 => Self::SendToReserve,
+            2 => Self::RollToLiquidity,
+            _ => return err!(ErrorCode::AccountDidNotDeserialize),
+        })
+    }
+}
+
+#[zero_copy]
+#[derive(Default, PartialEq, Eq)]
+pub struct Stage {
+    /// price per whole token (cents)
+    pub price: u64,
+    /// raw units for sale in this stage
+    pub cap: u64,
+    /// raw units allocated so far
+    pub sold: u64,
+    /// scheduled start (0 = only opens on sell-out of the previous stage; ignored for stage 1)
+    pub start: i64,
+}
+
+impl Stage {
+    /// Raw units still for sale.
+    pub fn remaining(&self) -> u64 {
+        self.cap.saturating_sub(self.sold)
+    }
+}
+
+#[zero_copy]
+#[derive(Default, PartialEq, Eq)]
+pub struct BonusTier {
+    /// purchase volume (USD cents) from which the tier applies
+    pub min_usd_cents: u64,
+    pub bps: u16,
+    pub padding: [u8; 6],
+}
+
+#[zero_copy]
+#[derive(Default, PartialEq, Eq)]
+pub struct Beneficiary {
+    pub wallet: Pubkey,
+    /// running total of lamports paid to this wallet by `distribute_treasury`
+    pub distributed: u64,
+    /// lamports held back because the wallet could not take them yet; paid with its next share
+    pub owed: u64,
+    pub share_bps: u16,
+    pub padding: [u8; 6],
+}
+
+impl PresaleState {
+    pub const LEN: usize = 8 + std::mem::size_of::<PresaleState>();
+
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
+    }
+
+    pub fn is_mint_finalized(&self) -> bool {
+        self.mint_finalized != 0
+    }
+
+    pub fn status(&self) -> Result<SaleStatus> {
+        SaleStatus::from_u8(self.status)
+    }
+
+    pub fn set_status(&mut self, status: SaleStatus) {
+        self.status = status as u8;
+    }
+
+    pub fn distribution_mode(&self) -> Result<DistributionMode> {
+        DistributionMode::from_u8(self.distribution_mode)
+    }
+
+    /// `None` until finalize_unsold has run.
+    pub fn unsold_action(&self) -> Result<Option<UnsoldAction>> {
+        match self.unsold_action {
+            0 => Ok(None),
+            value => UnsoldAction::from_u8(value - 1).map(Some),
+        }
+    }
+
+    pub fn set_unsold_action(&mut self, action: UnsoldAction) {
+        self.unsold_action = action as u8 + 1;
+    }
+
+    /// Allowlisted payout destinations currently in use.
+    pub fn payout_destinations(&self) -> &[Pubkey] {
+        &self.payout_destinations[..self.payout_destination_count as usize]
+    }
+
+    /// The owner is always a valid recipient; anything else must be allowlisted.
+    pub fn is_payout_destination(&self, recipient: &Pubkey) -> bool {
+        *recipient == self.owner || self.payout_destinations().contains(recipient)
+    }
+
+    /// Account for `amount` newly minted raw units, enforcing `max_supply`.
+    pub fn record_mint(&mut self, amount: u64) -> Result<()> {
+        let minted = self
+            .total_minted
+            .checked_add(amount)
+            .ok_or(PresaleError::MathOverflow)?;
+        require!(minted <= self.max_supply, PresaleError::MaxSupplyExceeded);
+        self.total_minted = minted;
+        Ok(())
+    }
+
+    pub fn expired_stage_policy(&self) -> Result<ExpiredStagePolicy> {
+        ExpiredStagePolicy::from_u8(self.expired_stage_policy)
+    }
+
+    pub fn pricing_mode(&self) -> Result<PricingMode> {
+        PricingMode::from_u8(self.pricing_mode)
+    }
+
+    /// Buyers commit SOL and receive tokens only once the sale is settled.
+    pub fn is_commit_sale(&self) -> Result<bool> {
+        Ok(matches!(
+            self.pricing_mode()?,
+            PricingMode::Auction | PricingMode::ProRata
+        ))
+    }
+
+    pub fn is_auction_settled(&self) -> bool {
+        self.auction_settled != 0
+    }
+
+    /// USD micro-cents worth of `lamports` at `usd_per_sol`.
+    pub fn lamports_to_micro_cents(&self, lamports: u64) -> Result<u128> {
+        Ok((lamports as u128)
+            .checked_mul(self.usd_per_sol as u128)
+            .and_then(|v| v.checked_mul(100 * CURVE_PRICE_SCALE))
+            .ok_or(PresaleError::MathOverflow)?
+            / LAMPORTS_PER_SOL)
+    }
+
+    /// Lamports (rounded up) paying for `raw` units at `price` micro-cents per whole token.
+    pub fn cost_in_lamports(&self, raw: u128, price: u64) -> Result<u64> {
+        let denominator = TOKEN_BASE
+            .checked_mul(self.usd_per_sol as u128)
+            .and_then(|v| v.checked_mul(100 * CURVE_PRICE_SCALE))
+            .filter(|v| *v > 0)
+            .ok_or(PresaleError::InvalidOraclePrice)?;
+        raw.checked_mul(price as u128)
+            .and_then(|v| v.checked_mul(LAMPORTS_PER_SOL))
+            .map(|v| v.div_ceil(denominator))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+
+    /// Limit price of auction bucket `bucket`.
+    pub fn auction_bucket_price(&self, bucket: usize) -> Result<u64> {
+        (bucket as u64)
+            .checked_mul(self.auction_tick)
+            .and_then(|v| v.checked_add(self.auction_floor_price))
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+
+    /// Bucket for a bid limited to `limit_price` (rounded down to the tick grid); bids without
+    /// a limit take any price and sit in the top bucket.
+    pub fn auction_bucket_for(&self, limit_price: Option<u64>) -> Result<u8> {
+        let top = AUCTION_BUCKETS - 1;
+        let bucket = match limit_price {
+            None => top,
+            Some(limit) => {
+                let above_floor = limit
+                    .checked_sub(self.auction_floor_price)
+                    .ok_or(PresaleError::BidBelowFloor)?;
+                ((above_floor / self.auction_tick.max(1)) as usize).min(top)
+            }
+        };
+        Ok(bucket as u8)
+    }
+
+    /// Raw units and cost (lamports) a settled auction gives a bid of `committed` lamports in
+    /// `bucket`. Bids above the marginal bucket fill in full at the clearing price, the
+    /// marginal bucket shares `marginal_fill_raw` pro rata and lower bids get nothing.
+    pub fn auction_fill(
+        &self,
+        book: &AuctionBook,
+        committed: u64,
+        bucket: u8,
+    ) -> Result<(u64, u64)> {
+        require!(self.is_auction_settled(), PresaleError::AuctionNotSettled);
+        let raw: u128 = match bucket.cmp(&self.marginal_bucket) {
+            Ordering::Greater => {
+                self.lamports_to_micro_cents(committed)?
+                    .checked_mul(TOKEN_BASE)
+                    .ok_or(PresaleError::MathOverflow)?
+                    / self.clearing_price as u128
+            }
+            Ordering::Equal => (self.marginal_fill_raw as u128)
+                .checked_mul(committed as u128)
+                .ok_or(PresaleError::MathOverflow)?
+                .checked_div(book.buckets[bucket as usize] as u128)
+                .unwrap_or(0),
+            Ordering::Less => 0,
+        };
+        // rounding can put the cost a lamport above the bid
+        let cost = self
+            .cost_in_lamports(raw, self.clearing_price)?
+            .min(committed);
+        Ok((
+            raw.try_into().map_err(|_| PresaleError::MathOverflow)?,
+            cost,
+        ))
+    }
+
+    /// Whole tokens a budget of `budget` micro-cents buys on the bonding curve, and their
+    /// exact cost (rounded up). Supply and position come from stage 1, which holds the whole
+    /// curve. The price at position `x` of `supply` is `p0 + (p1 - p0) * x / supply`, so
+    /// buying `n` tokens from `x` costs `n * p0 + (p1 - p0) * (2xn + n^2) / (2 * supply)`;
+    /// solving that for `n` gives the closed form below. With `p0 == p1` this is exactly
+    /// the ladder's `floor(cents / price)` for a stage priced at `p0 / CURVE_PRICE_SCALE`.
+    pub fn curve_quote(&self, budget: u128) -> Result<(u128, u128)> {
+        let stage = self.stage(1)?;
+        let supply = stage.cap as u128 / TOKEN_BASE;
+        let sold = stage.sold as u128 / TOKEN_BASE;
+        let available = supply.saturating_sub(sold);
+        if available == 0 || budget == 0 {
+            return Ok((0, 0));
+        }
+        let p0 = self.curve_start_price as u128;
+        let slope = (self.curve_end_price as u128)
+            .checked_sub(p0)
+            .ok_or(PresaleError::MathOverflow)?;
+
+        // n = 2 * S * B / (b + sqrt(b^2 + 2 * D * S * B)) with b = S * p0 + D * x
+        let two_sb = supply
+            .checked_mul(budget)
+            .and_then(|v| v.checked_mul(2))
+            .ok_or(PresaleError::MathOverflow)?;
+        let b = supply
+            .checked_mul(p0)
+            .and_then(|v| v.checked_add(slope.checked_mul(sold)?))
+            .ok_or(PresaleError::MathOverflow)?;
+        let disc = b
+            .checked_mul(b)
+            .and_then(|v| v.checked_add(slope.checked_mul(two_sb)?))
+            .ok_or(PresaleError::MathOverflow)?;
+        let denom = b
+            .checked_add(isqrt(disc))
+            .ok_or(PresaleError::MathOverflow)?;
+        let mut tokens = two_sb
+            .checked_div(denom)
+            .ok_or(PresaleError::MathOverflow)?
+            .min(available);
+
+        // the integer root can overshoot by a token; step back until the cost fits
+        let mut cost = self.curve_cost(sold, tokens)?;
+        while tokens > 0 && cost > budget {
+            tokens -= 1;
+            cost = self.curve_cost(sold, tokens)?;
+        }
+        Ok((tokens, cost))
+    }
+
+    /// Cost in micro-cents (rounded up) of `tokens` whole tokens bought from position `sold`.
+    pub fn curve_cost(&self, sold: u128, tokens: u128) -> Result<u128> {
+        let supply = (self.stage(1)?.cap as u128 / TOKEN_BASE).max(1);
+        let p0 = self.curve_start_price as u128;
+        let slope = (self.curve_end_price as u128)
+            .checked_sub(p0)
+            .ok_or(PresaleError::MathOverflow)?;
+        let two_s = supply.checked_mul(2).ok_or(PresaleError::MathOverflow)?;
+
+        let numerator = two_s
+            .checked_mul(p0)
+            .and_then(|v| v.checked_mul(tokens))
+            .and_then(|flat| {
+                let rise = sold
+                    .checked_mul(2)?
+                    .checked_add(tokens)?
+                    .checked_mul(tokens)?;
+                flat.checked_add(slope.checked_mul(rise)?)
+            })
+            .ok_or(PresaleError::MathOverflow)?;
+        Ok(numerator.div_ceil(two_s))
+    }
+
+    /// Stages currently in the ladder.
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages[..self.stage_count as usize]
+    }
+
+    /// Stage number `stage` (1-based).
+    pub fn stage(&self, stage: u8) -> Result<&Stage> {
+        self.stages()
+            .get((stage as usize).wrapping_sub(1))
+            .ok_or(PresaleError::InvalidStage.into())
+    }
+
+    pub fn stage_mut(&mut self, stage: u8) -> Result<&mut Stage> {
+        let count = self.stage_count as usize;
+        self.stages[..count]
+            .get_mut((stage as usize).wrapping_sub(1))
+            .ok_or(PresaleError::InvalidStage.into())
+    }
+
+    /// Latest stage whose scheduled start has passed at `now` (1 if none).
+    pub fn scheduled_stage(&self, now: i64) -> u8 {
+        self.stages()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .rfind(|(_, s)| s.start != 0 && now >= s.start)
+            .map(|(i, _)| i as u8 + 1)
+            .unwrap_or(1)
+    }
+
+    /// Stage buyers get at `now`: the further of sell-out and time-based progression.
+    pub fn active_stage(&self, now: i64) -> u8 {
+        self.current_stage.max(self.scheduled_stage(now))
+    }
+
+    /// Move `current_stage` up to the scheduled stage at `now`. Each stage skipped over has
+    /// its cap cut to what it sold; the rest is discarded or rolled into the next stage.
+    pub fn advance_stage(&mut self, now: i64) -> Result<()> {
+        let target = self.scheduled_stage(now);
+        let roll_forward = self.expired_stage_policy()? == ExpiredStagePolicy::RollForward;
+
+        while self.current_stage < target {
+            let stage = self.current_stage;
+            let expired = self.stage_mut(stage)?;
+            let unsold_raw = expired.remaining();
+            expired.cap = expired.sold;
+
+            if roll_forward {
+                let next = self.stage_mut(stage + 1)?;
+                next.cap = next
+                    .cap
+                    .checked_add(unsold_raw)
+                    .ok_or(PresaleError::MathOverflow)?;
+            }
+            self.current_stage = stage + 1;
+
+            emit!(StageExpired {
+                stage,
+                unsold_raw,
+                rolled_forward: roll_forward,
+            });
+        }
+        Ok(())
+    }
+
+    /// Anti-sniping: push presale_end back if a purchase of `lamports` at `now` is above the
+    /// threshold and lands within the window before the end, until the extension budget is
+    /// spent.
+    pub fn extend_for_purchase(&mut self, now: i64, lamports: u64) -> Result<()> {
+        if self.extension_threshold_lamports == 0
+            || lamports <= self.extension_threshold_lamports
+            || self.presale_end.saturating_sub(now) > self.extension_window as i64
+        {
+            return Ok(());
+        }
+        let extension = self
+            .extension_step
+            .min(self.max_extension.saturating_sub(self.total_extension));
+        if extension == 0 {
+            return Ok(());
+        }
+
+        self.presale_end = self
+            .presale_end
+            .checked_add(extension as i64)
+            .ok_or(PresaleError::MathOverflow)?;
+        self.total_extension += extension;
+
+        emit!(SaleExtended {
+            presale_end: self.presale_end,
+            extension,
+            total_extension: self.total_extension,
+        });
+        Ok(())
+    }
+
+    /// Count `allocated_raw` against the per-slot ceiling for `slot`.
+    pub fn record_slot_allocation(&mut self, slot: u64, allocated_raw: u64) -> Result<()> {
+        if slot != self.rate_limit_slot {
+            self.rate_limit_slot = slot;
+            self.slot_allocated_raw = 0;
+        }
+        let allocated = self
+            .slot_allocated_raw
+            .checked_add(allocated_raw)
+            .ok_or(PresaleError::MathOverflow)?;
+        require!(
+            self.slot_cap_raw == 0 || allocated <= self.slot_cap_raw,
+            PresaleError::SlotCapExceeded
+        );
+        self.slot_allocated_raw = allocated;
+        Ok(())
+    }
+
+    pub fn volume_basis(&self) -> Result<VolumeBasis> {
+        VolumeBasis::from_u8(self.volume_basis)
+    }
+
+    /// Volume bonus tiers currently in use, by increasing threshold.
+    pub fn bonus_tiers(&self) -> &[BonusTier] {
+        &self.bonus_tiers[..self.bonus_tier_count as usize]
+    }
+
+    /// Take the bonus on `base_raw` out of the pool: the early-bird rate while it runs plus
+    /// the highest tier `volume_usd_cents` reaches, clipped to what is left in the pool.
+    pub fn allocate_bonus(
+        &mut self,
+        now: i64,
+        base_raw: u64,
+        volume_usd_cents: u64,
+    ) -> Result<u64> {
+        let early_bird_ends = self
+            .presale_start
+            .saturating_add(self.early_bird_duration as i64);
+        let mut bps = 0u64;
+        if now < early_bird_ends {
+            bps += self.early_bird_bps as u64;
+        }
+        if let Some(tier) = self
+            .bonus_tiers()
+            .iter()
+            .rfind(|tier| volume_usd_cents >= tier.min_usd_cents)
+        {
+            bps += tier.bps as u64;
+        }
+
+        let bonus = (base_raw as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let bonus = bonus.min(self.bonus_pool_cap.saturating_sub(self.bonus_allocated));
+        self.bonus_allocated = self
+            .bonus_allocated
+            .checked_add(bonus)
+            .ok_or(PresaleError::MathOverflow)?;
+        Ok(bonus)
+    }
+
+    /// Every stage cap, the reserve and the bonus pool must fit under the hard supply cap.
+    pub fn check_supply(&self) -> Result<()> {
+        require!(
+            self.sale_supply()?
+                .checked_add(self.reserved_supply)
+                .and_then(|supply| supply.checked_add(self.bonus_pool_cap))
+                .ok_or(PresaleError::MathOverflow)?
+                <= self.max_supply,
+            PresaleError::InvalidSupplyConfig
+        );
+        Ok(())
+    }
+
+    /// Raw units a pre-funded sale can hand out: every stage cap plus the bonus pool.
+    pub fn required_inventory(&self) -> Result<u64> {
+        self.sale_supply()?
+            .checked_add(self.bonus_pool_cap)
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+
+    /// Once a pre-funded sale has started, the deposits must cover its whole inventory, so
+    /// the vault can never run dry partway through a stage.
+    pub fn check_inventory(&self, now: i64) -> Result<()> {
+        if self.distribution_mode()? != DistributionMode::PreFundedVault || now < self.presale_start
+        {
+            return Ok(());
+        }
+        require!(
+            self.required_inventory()? <= self.total_deposited,
+            PresaleError::InsufficientInventory
+        );
+        Ok(())
+    }
+
+    /// Sum of all stage caps (raw).
+    pub fn sale_supply(&self) -> Result<u64> {
+        self.stages()
+            .iter()
+            .try_fold(0u64, |acc, stage| acc.checked_add(stage.cap))
+            .ok_or(PresaleError::MathOverflow.into())
+    }
+
+    /// Allocated raw units, bonus included, not yet claimed by buyers.
+    pub fn unclaimed_raw(&self) -> Result<u64> {
+        Ok(self
+            .total_allocated
+            .checked_add(self.bonus_allocated)
+            .and_then(|allocated| allocated.checked_sub(self.total_claimed))
+            .ok_or(PresaleError::MathOverflow)?)
+    }
+
+    /// Claims are transferred out of the vault instead of minted.
+    pub fn claims_from_vault(&self) -> Result<bool> {
+        Ok(self.distribution_mode()? == DistributionMode::PreFundedVault
+            || self.is_mint_finalized())
+    }
+
+    /// Every stage cap is fully allocated.
+    pub fn is_sold_out(&self) -> Result<bool> {
+        Ok(self.total_allocated >= self.sale_supply()?)
+    }
+
+    /// Sale is finalized or cancelled and can never be reopened.
+    pub fn is_locked(&self) -> Result<bool> {
+        Ok(matches!(
+            self.status()?,
+            SaleStatus::Finalized | SaleStatus::Cancelled
+        ))
+    }
+
+    /// Lifecycle at `now`; terminal states are sticky.
+    pub fn effective_status(&self, now: i64) -> Result<SaleStatus> {
+        let status = self.status()?;
+        Ok(match status {
+            SaleStatus::Finalized | SaleStatus::Cancelled => status,
+            _ if self.is_sold_out()? || now > self.presale_end => SaleStatus::Ended,
+            _ if now < self.presale_start => SaleStatus::Pending,
+            _ => SaleStatus::Live,
+        })
+    }
+
+    /// Sale has not closed yet at `now`, so purchases may still come in.
+    pub fn is_open(&self, now: i64) -> Result<bool> {
+        Ok(matches!(
+            self.effective_status(now)?,
+            SaleStatus::Pending | SaleStatus::Live
+        ))
+    }
+
+    /// Store the lifecycle at `now` and return it.
+    pub fn refresh_status(&mut self, now: i64) -> Result<SaleStatus> {
+        let status = self.effective_status(now)?;
+        let from = self.status()?;
+        if status != from {
+            emit!(SaleStatusChanged { from, to: status });
+            self.set_status(status);
+        }
+        Ok(status)
+    }
+
+    /// Revenue-split beneficiaries currently in use.
+    pub fn beneficiaries(&self) -> &[Beneficiary] {
+        &self.beneficiaries[..self.beneficiary_count as usize]
+    }
+
+    /// Treasury lamports held back for beneficiaries that could not be paid yet.
+    pub fn beneficiaries_owed(&self) -> Result<u64> {
+        self.beneficiaries().iter().try_fold(0u64, |total, b| {
+            total
+                .checked_add(b.owed)
+                .ok_or_else(|| PresaleError::MathOverflow.into())
+        })
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    const MICRO: u64 = CURVE_PRICE_SCALE as u64;
+
+    fn curve_state(start_price: u64, end_price: u64, supply: u64, sold: u64) -> PresaleState {
+        let mut state = PresaleState::zeroed();
+        state.stage_count = 1;
+        state.stages[0].cap = supply * TOKEN_BASE as u64;
+        state.stages[0].sold = sold * TOKEN_BASE as u64;
+        state.pricing_mode = PricingMode::LinearCurve as u8;
+        state.curve_start_price = start_price;
+        state.curve_end_price = end_price;
+        state
+    }
+
+    #[test]
+    fn flat_curve_matches_ladder_stage() {
+        // a ladder stage at 3 cents sells floor(cents / 3) tokens
+        let state = curve_state(3 * MICRO, 3 * MICRO, 1_000_000, 0);
+        for cents in [0u128, 2, 3, 4, 299, 300, 301, 1_000_000] {
+            let (tokens, cost) = state.curve_quote(cents * CURVE_PRICE_SCALE).unwrap();
+            assert_eq!(tokens, cents / 3);
+            assert_eq!(cost, tokens * 3 * CURVE_PRICE_SCALE);
+        }
+    }
+
+    #[test]
+    fn curve_stops_at_supply_like_a_sold_out_stage() {
+        let state = curve_state(MICRO, 5 * MICRO, 1_000, 990);
+        let (tokens, _) = state.curve_quote(u64::MAX as u128).unwrap();
+        assert_eq!(tokens, 10);
+
+        let state = curve_state(MICRO, 5 * MICRO, 1_000, 1_000);
+        assert_eq!(state.curve_quote(u64::MAX as u128).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn rising_curve_buys_the_largest_affordable_amount() {
+        let state = curve_state(MICRO, 5 * MICRO, 1_000_000, 250_000);
+        for budget in [1u128, 999_999, 2_000_000, 123_456_789, 50_000_000_000] {
+            let (tokens, cost) = state.curve_quote(budget).unwrap();
+            assert!(cost <= budget);
+            assert!(state.curve_cost(250_000, tokens + 1).unwrap() > budget);
+        }
+    }
+
+    #[test]
+    fn whole_curve_costs_its_average_price() {
+        // 1..5 cents over 1M tokens averages 3 cents
+        let state = curve_state(MICRO, 5 * MICRO, 1_000_000, 0);
+        assert_eq!(
+            state.curve_cost(0, 1_000_000).unwrap(),
+            3 * CURVE_PRICE_SCALE * 1_000_000
+        );
+    }
+
+    /// A ladder of 100-unit stages scheduled at `starts`, at stage 1.
+    fn ladder_state(policy: ExpiredStagePolicy, starts: &[i64]) -> PresaleState {
+        let mut state = PresaleState::zeroed();
+        state.stage_count = starts.len() as u8;
+        for (i, start) in starts.iter().enumerate() {
+            state.stages[i].price = i as u64 + 1;
+            state.stages[i].cap = 100;
+            state.stages[i].start = *start;
+        }
+        state.current_stage = 1;
+        state.expired_stage_policy = policy as u8;
+        state
+    }
+
+    fn caps(state: &PresaleState) -> Vec<u64> {
+        state.stages().iter().map(|s| s.cap).collect()
+    }
+
+    #[test]
+    fn advances_past_several_stages_in_one_call() {
+        // stage 3 has no start of its own, but stage 4's start carries the ladder past it
+        let mut state = ladder_state(ExpiredStagePolicy::Discard, &[0, 100, 0, 300]);
+        state.stages[0].sold = 30;
+        state.stages[1].sold = 10;
+        assert_eq!(state.scheduled_stage(299), 2);
+        assert_eq!(state.scheduled_stage(300), 4);
+
+        state.advance_stage(350).unwrap();
+        assert_eq!(state.current_stage, 4);
+        assert_eq!(caps(&state), [30, 10, 0, 100]);
+
+        // nothing left to move past
+        state.advance_stage(1_000).unwrap();
+        assert_eq!(state.current_stage, 4);
+        assert_eq!(caps(&state), [30, 10, 0, 100]);
+    }
+
+    #[test]
+    fn roll_forward_adds_the_leftover_cap_to_the_next_stage() {
+        let mut state = ladder_state(ExpiredStagePolicy::RollForward, &[0, 100, 200]);
+        state.stages[0].sold = 30;
+
+        state.advance_stage(150).unwrap();
+        assert_eq!(state.current_stage, 2);
+        assert_eq!(caps(&state), [30, 170, 100]);
+
+        // rolled capacity keeps rolling if it does not sell either
+        state.stages[1].sold = 20;
+        state.advance_stage(200).unwrap();
+        assert_eq!(state.current_stage, 3);
+        assert_eq!(caps(&state), [30, 20, 250]);
+        assert_eq!(state.sale_supply().unwrap(), 300);
+    }
+
+    #[test]
+    fn discard_drops_the_leftover_cap() {
+        let mut state = ladder_state(ExpiredStagePolicy::Discard, &[0, 100, 200]);
+        state.stages[0].sold = 30;
+
+        state.advance_stage(150).unwrap();
+        assert_eq!(state.current_stage, 2);
+        assert_eq!(caps(&state), [30, 100, 100]);
+        assert_eq!(state.sale_supply().unwrap(), 230);
+    }
+
+    #[test]
+    fn stages_sold_out_ahead_of_schedule_are_left_alone() {
+        let mut state = ladder_state(ExpiredStagePolicy::Discard, &[0, 200, 300]);
+        // stage 1 sold out, which already moved the sale on to stage 2
+        state.stages[0].sold = 100;
+        state.current_stage = 2;
+        state.total_allocated = 100;
+
+        state.advance_stage(150).unwrap();
+        assert_eq!(state.scheduled_stage(150), 1);
+        assert_eq!(state.active_stage(150), 2);
+        assert_eq!(state.current_stage, 2);
+        assert_eq!(caps(&state), [100, 100, 100]);
+
+        // stage 2 runs out of time part sold; discarding the rest brings the sell-out closer
+        state.stages[1].sold = 60;
+        state.total_allocated = 160;
+        state.advance_stage(300).unwrap();
+        assert_eq!(state.current_stage, 3);
+        assert_eq!(caps(&state), [100, 60, 100]);
+        assert!(!state.is_sold_out().unwrap());
+
+        state.stages[2].sold = 100;
+        state.total_allocated = 260;
+        assert!(state.is_sold_out().unwrap());
+    }
+
+    fn auction_state(floor: u64, tick: u64) -> PresaleState {
+        let mut state = PresaleState::zeroed();
+        state.usd_per_sol = 100;
+        state.pricing_mode = PricingMode::Auction as u8;
+        state.auction_floor_price = floor;
+        state.auction_tick = tick;
+        state
+    }
+
+    #[test]
+    fn bids_land_in_the_bucket_at_or_below_their_limit() {
+        let state = auction_state(MICRO, MICRO / 10);
+        assert_eq!(state.auction_bucket_for(Some(MICRO)).unwrap(), 0);
+        assert_eq!(
+            state
+                .auction_bucket_for(Some(MICRO + MICRO / 10 - 1))
+                .unwrap(),
+            0
+        );
+        assert_eq!(state.auction_bucket_for(Some(2 * MICRO)).unwrap(), 10);
+        assert_eq!(state.auction_bucket_for(Some(u64::MAX)).unwrap(), 63);
+        assert_eq!(state.auction_bucket_for(None).unwrap(), 63);
+        assert!(state.auction_bucket_for(Some(MICRO - 1)).is_err());
+    }
+
+    #[test]
+    fn settled_bids_pay_the_clearing_price_and_keep_the_rest() {
+        let mut state = auction_state(MICRO, MICRO / 10);
+        let mut book = AuctionBook::zeroed();
+        book.buckets[5] = 4 * LAMPORTS_PER_SOL as u64;
+        state.auction_settled = 1;
+        state.clearing_price = 2 * MICRO;
+        state.marginal_bucket = 5;
+        state.marginal_fill_raw = 5_000 * TOKEN_BASE as u64;
+
+        // 1 SOL = $100 buys 5,000 tokens at 2 cents
+        let (raw, cost) = state
+            .auction_fill(&book, LAMPORTS_PER_SOL as u64, 6)
+            .unwrap();
+        assert_eq!(raw, 5_000 * TOKEN_BASE as u64);
+        assert_eq!(cost, LAMPORTS_PER_SOL as u64);
+
+        // a quarter of the marginal bucket gets a quarter of its fill and pays for it only
+        let (raw, cost) = state
+            .auction_fill(&book, LAMPORTS_PER_SOL as u64, 5)
+            .unwrap();
+        assert_eq!(raw, 1_250 * TOKEN_BASE as u64);
+        assert_eq!(cost, LAMPORTS_PER_SOL as u64 / 4);
+
+        assert_eq!(
+            state
+                .auction_fill(&book, LAMPORTS_PER_SOL as u64, 4)
+                .unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn oversubscribed_pro_rata_commits_share_the_cap() {
+        // 1,000 tokens at 10 cents = $100 = 1 SOL of capacity, 4 SOL committed
+        let mut state = auction_state(10 * MICRO, 1);
+        state.pricing_mode = PricingMode::ProRata as u8;
+        let mut book = AuctionBook::zeroed();
+        book.buckets[0] = 4 * LAMPORTS_PER_SOL as u64;
+        state.auction_settled = 1;
+        state.clearing_price = 10 * MICRO;
+        state.marginal_fill_raw = 1_000 * TOKEN_BASE as u64;
+
+        // min(commit, commit * cap / total) = 2 SOL * 1 / 4
+        let (raw, cost) = state
+            .auction_fill(&book, 2 * LAMPORTS_PER_SOL as u64, 0)
+            .unwrap();
+        assert_eq!(raw, 500 * TOKEN_BASE as u64);
+        assert_eq!(cost, LAMPORTS_PER_SOL as u64 / 2);
+    }
+
+    #[test]
+    fn late_large_purchases_extend_the_end_up_to_the_cap() {
+        let mut state = PresaleState::zeroed();
+        state.presale_end = 1_000;
+        state.extension_threshold_lamports = 100;
+        state.extension_window = 60;
+        state.extension_step = 50;
+        state.max_extension = 80;
+
+        // too small, then too early
+        state.extend_for_purchase(990, 100).unwrap();
+        state.extend_for_purchase(900, 101).unwrap();
+        assert_eq!(state.presale_end, 1_000);
+
+        state.extend_for_purchase(990, 101).unwrap();
+        state.extend_for_purchase(1_040, 101).unwrap();
+        state.extend_for_purchase(1_070, 101).unwrap();
+        assert_eq!(state.presale_end, 1_080);
+        assert_eq!(state.total_extension, 80);
+    }
+
+    #[test]
+    fn slot_ceiling_resets_on_a_new_slot() {
+        let mut state = PresaleState::zeroed();
+        state.slot_cap_raw = 100;
+
+        state.record_slot_allocation(7, 60).unwrap();
+        assert!(state.record_slot_allocation(7, 41).is_err());
+        state.record_slot_allocation(7, 40).unwrap();
+        assert_eq!(state.slot_allocated_raw, 100);
+
+        state.record_slot_allocation(8, 100).unwrap();
+        state.slot_cap_raw = 0;
+        state.record_slot_allocation(8, u64::MAX - 100).unwrap();
+    }
+
+    #[test]
+    fn bonus_adds_early_bird_and_highest_tier_until_the_pool_runs_dry() {
+        let mut state = PresaleState::zeroed();
+        state.presale_start = 1_000;
+        state.early_bird_duration = 100;
+        state.early_bird_bps = 1_000;
+        state.bonus_tiers[0].min_usd_cents = 500_000;
+        state.bonus_tiers[0].bps = 500;
+        state.bonus_tiers[1].min_usd_cents = 2_500_000;
+        state.bonus_tiers[1].bps = 1_000;
+        state.bonus_tier_count = 2;
+        state.bonus_pool_cap = 1_000;
+
+        // early bird only, then early bird plus the top tier
+        assert_eq!(state.allocate_bonus(1_050, 1_000, 100).unwrap(), 100);
+        assert_eq!(state.allocate_bonus(1_099, 1_000, 3_000_000).unwrap(), 200);
+        // early bird over: the first tier alone
+        assert_eq!(state.allocate_bonus(1_100, 1_000, 600_000).unwrap(), 50);
+        assert_eq!(state.allocate_bonus(1_100, 1_000, 499_999).unwrap(), 0);
+
+        // the pool only has 650 left
+        assert_eq!(state.allocate_bonus(1_100, 10_000, 3_000_000).unwrap(), 650);
+        assert_eq!(state.allocate_bonus(1_000, 10_000, 3_000_000).unwrap(), 0);
+        assert_eq!(state.bonus_allocated, 1_000);
+    }
+
+    #[test]
+    fn pre_funded_sale_must_hold_its_inventory_once_started() {
+        let mut state = PresaleState::zeroed();
+        state.distribution_mode = DistributionMode::PreFundedVault as u8;
+        state.presale_start = 1_000;
+        state.stage_count = 2;
+        state.stages[0].cap = 600;
+        state.stages[1].cap = 400;
+        state.bonus_pool_cap = 100;
+        state.total_deposited = 1_099;
+
+        // still being set up, so deposits can come later
+        state.check_inventory(999).unwrap();
+        assert!(state.check_inventory(1_000).is_err());
+        state.total_deposited = 1_100;
+        state.check_inventory(1_000).unwrap();
+
+        // mint-on-claim sales mint what they sell
+        state.distribution_mode = DistributionMode::MintOnClaim as u8;
+        state.total_deposited = 0;
+        state.check_inventory(1_000).unwrap();
+    }
+}
@@ -1,9 +1,41 @@
+use crate::constants::*;
+use crate::errors::PresaleError;
 use anchor_lang::prelude::*;
 
 #[account]
+#[derive(InitSpace)]
 pub struct UserAllocation {
     pub buyer: Pubkey,
     pub allocated_raw: u64,
     pub claimed_raw: u64,
     pub bump: u8,
-}
\ No newline at end of file
+    /// lamports paid into the treasury by this buyer (refunded if the sale is cancelled)
+    pub paid_lamports: u64,
+
+    // layout version (0 = written before versioning); new fields go between `version`
+    // and `reserved`, shrinking `reserved`
+    pub version: u8,
+    /// auction bid not yet turned into an allocation (lamports)
+    pub committed_lamports: u64,
+    /// auction price bucket the bid sits in
+    pub bid_bucket: u8,
+    /// unix time of the last buy_tokens, for the per-wallet cooldown
+    pub last_purchase_at: i64,
+    /// 1 while the owner holds this allocation's claims
+    pub frozen: u8,
+    /// bonus tokens credited on top of `allocated_raw` (raw)
+    pub bonus_raw: u64,
+    /// USD cents spent through buy_tokens, for cumulative volume tiers
+    pub purchased_usd_cents: u64,
+    pub reserved: [u8; USER_ALLOC_RESERVED_BYTES],
+}
+
+impl UserAllocation {
+    /// Everything the buyer can claim: base allocation plus bonus.
+    pub fn entitled_raw(&self) -> Result<u64> {
+        Ok(self
+            .allocated_raw
+            .checked_add(self.bonus_raw)
+            .ok_or(PresaleError::MathOverflow)?)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod math;
+pub mod token;
+
+pub use math::*;
+pub use token::*;
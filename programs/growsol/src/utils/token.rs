@@ -0,0 +1,19 @@
+use crate::errors::PresaleError;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::{get_mint_extension_data, spl_token_2022, Mint};
+
+/// Token-2022 transfer fee withheld when moving `amount` of `mint` this epoch
+/// (always 0 for classic SPL mints and Token-2022 mints without the extension).
+pub fn transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    match get_mint_extension_data::<TransferFeeConfig>(&mint_info) {
+        Ok(config) => Ok(config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(PresaleError::MathOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
@@ -0,0 +1,85 @@
+// scripts/auction.ts
+import * as anchor from "@coral-xyz/anchor";
+import { SystemProgram } from "@solana/web3.js";
+import dotenv from "dotenv";
+import {
+  provider,
+  program,
+  presaleStatePda,
+  treasuryPda,
+  auctionBookPda,
+  userAllocationPdaFor,
+  denylistPdaFor,
+} from "./common";
+
+dotenv.config();
+
+// prices are sent as micro-cents per whole token (1 USD = 10^8)
+const usdToMicroCents = (usd: string) => new anchor.BN(Math.round(Number(usd) * 1e8));
+
+// usage:
+//   ts-node scripts/auction.ts configure <floorUsd> <tickUsd> <supplyTokens>   (owner, before start)
+//   ts-node scripts/auction.ts pro-rata <priceUsd> <supplyTokens>              (owner, before start)
+//   ts-node scripts/auction.ts bid <sol> [limitUsd]                            (no limit for pro-rata)
+//   ts-node scripts/auction.ts settle                                          (anyone, after end)
+async function auction(args: string[]) {
+  const signer = provider.wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const auctionBook = auctionBookPda();
+  const methods = program.methods as any;
+
+  let tx: string;
+  switch (args[0]) {
+    case "configure":
+      tx = await methods
+        .configureAuction(usdToMicroCents(args[1]), usdToMicroCents(args[2]), new anchor.BN(args[3]))
+        .accounts({
+          owner: signer,
+          presaleState,
+          auctionBook,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      break;
+    case "pro-rata":
+      tx = await methods
+        .configureProRata(usdToMicroCents(args[1]), new anchor.BN(args[2]))
+        .accounts({
+          owner: signer,
+          presaleState,
+          auctionBook,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      break;
+    case "bid":
+      tx = await methods
+        .commitBid(
+          new anchor.BN(Math.floor(Number(args[1]) * 1e9)),
+          args[2] ? usdToMicroCents(args[2]) : null
+        )
+        .accounts({
+          bidder: signer,
+          presaleState,
+          auctionBook,
+          treasury: treasuryPda(),
+          userAllocation: userAllocationPdaFor(signer),
+          denylistEntry: denylistPdaFor(signer),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      break;
+    case "settle":
+      tx = await methods.settleAuction().accounts({ presaleState, auctionBook }).rpc();
+      break;
+    default:
+      throw new Error(
+        "usage: auction.ts configure <floorUsd> <tickUsd> <supplyTokens> | pro-rata <priceUsd> " +
+          "<supplyTokens> | bid <sol> [limitUsd] | settle"
+      );
+  }
+
+  console.log(`✅ Auction ${args[0]} done. Tx:`, tx);
+}
+
+auction(process.argv.slice(2)).catch(console.error);
@@ -0,0 +1,39 @@
+// scripts/bonus.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+const TOKEN_BASE = 1_000_000_000;
+
+// usage: ts-node scripts/bonus.ts <poolTokens> <earlyBirdPct> <earlyBirdHours> <per-purchase|cumulative> [minUsd:pct ...]
+// e.g. bonus.ts 10000000 10 48 per-purchase 5000:5 25000:10
+async function setBonus(args: string[]) {
+  if (args.length < 4) {
+    throw new Error(
+      "usage: bonus.ts <poolTokens> <earlyBirdPct> <earlyBirdHours> <per-purchase|cumulative> [minUsd:pct ...]"
+    );
+  }
+  const owner = anchor.getProvider().wallet.publicKey;
+  const pool = new anchor.BN(args[0]).mul(new anchor.BN(TOKEN_BASE));
+  const earlyBirdBps = Math.round(Number(args[1]) * 100);
+  const earlyBirdSecs = Math.round(Number(args[2]) * 3600);
+  const basis = args[3] === "cumulative" ? { cumulative: {} } : { perPurchase: {} };
+  const tiers = args.slice(4).map((tier) => {
+    const [usd, pct] = tier.split(":").map(Number);
+    return { minUsdCents: new anchor.BN(Math.round(usd * 100)), bps: Math.round(pct * 100) };
+  });
+
+  const tx = await (program.methods as any)
+    .adminSetBonus(earlyBirdBps, earlyBirdSecs, tiers, basis, pool)
+    .accounts({
+      owner,
+      presaleState: presaleStatePda(),
+    })
+    .rpc();
+
+  console.log("✅ Bonus configuration updated. Tx:", tx);
+}
+
+setBonus(process.argv.slice(2)).catch(console.error);
@@ -2,7 +2,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
 import dotenv from "dotenv";
-import { provider, program, userAllocationPdaFor, presaleStatePda, mintAuthPda, treasuryPda } from "./common";
+import { provider, program, userAllocationPdaFor, denylistPdaFor, presaleStatePda, mintAuthPda, treasuryPda, ataFor, tokenProgramId } from "./common";
 
 dotenv.config();
 
@@ -26,6 +26,9 @@ async function buy(amountSol: number) {
   const lamports = Math.floor(amountSol * 1e9);
   const amountLamportsBN = new anchor.BN(lamports);
 
+  // presale vault (checked for inventory in pre-funded mode)
+  const presaleTokenAccount = ataFor(mint, presaleState);
+
   const tx = await (program.methods as any)
     .buyTokens(amountLamportsBN)
     .accounts({
@@ -34,8 +37,10 @@ async function buy(amountSol: number) {
       treasury,
       mintAuth,
       mint,
+      presaleTokenAccount,
       userAllocation: userAlloc,
-      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      denylistEntry: denylistPdaFor(buyer),
+      tokenProgram: tokenProgramId(),
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
     })
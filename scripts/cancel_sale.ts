@@ -0,0 +1,24 @@
+// scripts/cancel_sale.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+async function cancelSale() {
+  const owner = anchor.getProvider().wallet.publicKey;
+  const presaleState = presaleStatePda();
+
+  // irreversible: withdrawals and claims stop, buyers can call `refund`
+  const tx = await (program.methods as any)
+    .cancelSale()
+    .accounts({
+      owner,
+      presaleState,
+    })
+    .rpc();
+
+  console.log("✅ Sale cancelled. Tx:", tx);
+}
+
+cancelSale().catch(console.error);
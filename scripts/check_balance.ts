@@ -2,7 +2,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
 import dotenv from "dotenv";
-import { provider } from "./common";
+import { provider, ataFor } from "./common";
 
 dotenv.config();
 const p = provider;
@@ -12,10 +12,7 @@ async function checkBalance(buyerPubkeyString?: string) {
   const buyer = buyerPubkeyString ? new PublicKey(buyerPubkeyString) : p.wallet.publicKey;
   const mint = new PublicKey(process.env.MINT_ADDRESS!);
 
-  const ata = await anchor.utils.token.associatedAddress({
-    mint,
-    owner: buyer,
-  });
+  const ata = ataFor(mint, buyer);
 
   console.log("Buyer:", buyer.toBase58());
   console.log("Mint:", mint.toBase58());
@@ -2,7 +2,18 @@
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
 import dotenv from "dotenv";
-import { provider, program, userAllocationPdaFor, presaleStatePda, mintAuthPda } from "./common";
+import {
+  provider,
+  program,
+  userAllocationPdaFor,
+  denylistPdaFor,
+  presaleStatePda,
+  mintAuthPda,
+  treasuryPda,
+  auctionBookPda,
+  ataFor,
+  tokenProgramId,
+} from "./common";
 
 dotenv.config();
 
@@ -16,10 +27,15 @@ async function claim(buyerArg?: string) {
   const userAlloc = userAllocationPdaFor(buyer);
 
   // buyer's ATA (will be init_if_needed by instruction)
-  const userAta = await anchor.utils.token.associatedAddress({
-    mint,
-    owner: buyer,
-  });
+  const userAta = ataFor(mint, buyer);
+
+  // presale vault (claims are paid from here once the mint authority is revoked)
+  const presaleTokenAccount = ataFor(mint, presaleState);
+
+  // auction sales settle the bid on claim and need the book; other sales leave it out
+  const auctionBook = (await provider.connection.getAccountInfo(auctionBookPda()))
+    ? auctionBookPda()
+    : null;
 
   console.log("Buyer:", buyer.toBase58());
   console.log("Mint:", mint.toBase58());
@@ -27,15 +43,19 @@ async function claim(buyerArg?: string) {
   console.log("User allocation PDA:", userAlloc.toBase58());
 
   const tx = await (program.methods as any)
-    .claimTokens()
+    .claimTokens(closeWhenDone)
     .accounts({
       buyer,
       presaleState,
+      treasury: treasuryPda(),
+      auctionBook,
       mintAuth,
       mint,
       userAllocation: userAlloc,
+      denylistEntry: denylistPdaFor(buyer),
+      presaleTokenAccount,
       userTokenAccount: userAta,
-      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenProgram: tokenProgramId(),
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
@@ -45,4 +65,6 @@ async function claim(buyerArg?: string) {
 }
 
 const arg = process.argv[2]; // optional: pass a buyer pubkey (for admin use, otherwise uses provider wallet)
-claim(arg).catch(console.error);
+// pass --close to close the allocation and reclaim its rent once everything is claimed
+const closeWhenDone = process.argv.includes("--close");
+claim(arg === "--close" ? undefined : arg).catch(console.error);
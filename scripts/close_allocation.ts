@@ -0,0 +1,24 @@
+// scripts/close_allocation.ts
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda, userAllocationPdaFor } from "./common";
+
+dotenv.config();
+
+async function closeAllocation() {
+  const buyer = anchor.getProvider().wallet.publicKey;
+
+  // only fully claimed allocations can be closed; the rent goes back to the buyer
+  const tx = await (program.methods as any)
+    .closeAllocation()
+    .accounts({
+      buyer,
+      presaleState: presaleStatePda(),
+      userAllocation: userAllocationPdaFor(buyer),
+    })
+    .rpc();
+
+  console.log("✅ Allocation closed. Tx:", tx);
+}
+
+closeAllocation().catch(console.error);
@@ -1,6 +1,11 @@
 // scripts/common.ts
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
 import dotenv from "dotenv";
 import dayjs from "dayjs";
 
@@ -26,6 +31,21 @@ export function treasuryPda(): PublicKey {
   return PublicKey.findProgramAddressSync([Buffer.from("treasury")], program.programId)[0];
 }
 
+export function auctionBookPda(): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("auction_book")], program.programId)[0];
+}
+
+/**
+ * Derive the denylist marker PDA for a wallet (exists only while the wallet is denylisted)
+ * seeds: ["denylisted", wallet]
+ */
+export function denylistPdaFor(wallet: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("denylisted"), wallet.toBuffer()],
+    program.programId
+  )[0];
+}
+
 /**
  * Derive user_allocation PDA for a given buyer pubkey
  * seeds: ["user_alloc", presale_state.key(), buyer.key()]
@@ -38,6 +58,18 @@ export function userAllocationPdaFor(buyer: PublicKey): PublicKey {
   )[0];
 }
 
+/**
+ * Token program of the sale mint: set TOKEN_PROGRAM=token-2022 in .env for Token-2022 mints.
+ */
+export function tokenProgramId(): PublicKey {
+  return process.env.TOKEN_PROGRAM === "token-2022" ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+}
+
+/** ATA of `owner` for `mint` under the sale's token program (PDAs allowed as owner). */
+export function ataFor(mint: PublicKey, owner: PublicKey): PublicKey {
+  return getAssociatedTokenAddressSync(mint, owner, true, tokenProgramId());
+}
+
 export function parseDateToUnix(dateStr: string): number {
   const parsed = dayjs(dateStr);
   if (!parsed.isValid()) {
@@ -0,0 +1,53 @@
+// scripts/denylist.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { program, presaleStatePda, denylistPdaFor, userAllocationPdaFor } from "./common";
+
+dotenv.config();
+
+// usage: ts-node scripts/denylist.ts add|remove|freeze|unfreeze <wallet>
+// add/remove block the wallet from buying and claiming; freeze/unfreeze hold its claims
+async function denylist(args: string[]) {
+  if (args.length !== 2) {
+    throw new Error("usage: denylist.ts add|remove|freeze|unfreeze <wallet>");
+  }
+  const owner = anchor.getProvider().wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const wallet = new PublicKey(args[1]);
+  const methods = program.methods as any;
+
+  let tx: string;
+  switch (args[0]) {
+    case "add":
+      tx = await methods
+        .adminDenylistAdd(wallet)
+        .accounts({
+          owner,
+          presaleState,
+          denylistEntry: denylistPdaFor(wallet),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      break;
+    case "remove":
+      tx = await methods
+        .adminDenylistRemove(wallet)
+        .accounts({ owner, presaleState, denylistEntry: denylistPdaFor(wallet) })
+        .rpc();
+      break;
+    case "freeze":
+    case "unfreeze":
+      tx = await methods
+        .adminSetAllocationFrozen(wallet, args[0] === "freeze")
+        .accounts({ owner, presaleState, userAllocation: userAllocationPdaFor(wallet) })
+        .rpc();
+      break;
+    default:
+      throw new Error("usage: denylist.ts add|remove|freeze|unfreeze <wallet>");
+  }
+
+  console.log(`✅ ${args[0]} ${wallet.toBase58()}. Tx:`, tx);
+}
+
+denylist(process.argv.slice(2)).catch(console.error);
@@ -0,0 +1,43 @@
+// scripts/deposit_tokens.ts // FOR OWNERS ONLY (pre-funded presales)
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { provider, program, presaleStatePda, ataFor, tokenProgramId } from "./common";
+
+dotenv.config();
+
+async function depositTokens(amountRaw: number) {
+  const owner = provider.wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const mint = new PublicKey(process.env.MINT_ADDRESS!);
+
+  const ownerTokenAccount = ataFor(mint, owner);
+  const presaleTokenAccount = ataFor(mint, presaleState);
+
+  console.log("Owner:", owner.toBase58());
+  console.log("Mint:", mint.toBase58());
+  console.log("Presale token ATA:", presaleTokenAccount.toBase58());
+  console.log("Amount (raw units):", amountRaw);
+
+  const tx = await (program.methods as any)
+    .depositTokens(new anchor.BN(amountRaw))
+    .accounts({
+      owner,
+      presaleState,
+      mint,
+      ownerTokenAccount,
+      presaleTokenAccount,
+      tokenProgram: tokenProgramId(),
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .rpc();
+
+  console.log("✅ Deposit tx:", tx);
+}
+
+const amtRaw = Number(process.argv[2]) || 0;
+if (!amtRaw || amtRaw <= 0) {
+  console.error("Usage: node scripts/deposit_tokens.ts <amount_raw>");
+  process.exit(1);
+}
+depositTokens(amtRaw).catch(console.error);
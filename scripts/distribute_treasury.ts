@@ -0,0 +1,44 @@
+// scripts/distribute_treasury.ts // permissionless
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { provider, program, presaleStatePda, treasuryPda } from "./common";
+
+dotenv.config();
+
+async function distribute() {
+  const caller = provider.wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const treasury = treasuryPda();
+
+  const state = await (program.account as any).presaleState.fetch(presaleState);
+  const count: number = state.beneficiaryCount;
+  const beneficiaries = state.beneficiaries.slice(0, count);
+
+  console.log("Caller:", caller.toBase58());
+  console.log("Treasury PDA:", treasury.toBase58());
+  for (const b of beneficiaries) {
+    console.log(`Beneficiary ${b.wallet.toBase58()} → ${b.shareBps} bps (paid so far: ${b.distributed}, held back: ${b.owed})`);
+  }
+
+  // beneficiary wallets go in as writable remaining accounts, in configuration order
+  const tx = await (program.methods as any)
+    .distributeTreasury()
+    .accounts({
+      caller,
+      presaleState,
+      treasury,
+    })
+    .remainingAccounts(
+      beneficiaries.map((b: { wallet: PublicKey }) => ({
+        pubkey: b.wallet,
+        isWritable: true,
+        isSigner: false,
+      }))
+    )
+    .rpc();
+
+  console.log("✅ Distribute tx:", tx);
+}
+
+distribute().catch(console.error);
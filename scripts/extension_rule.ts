@@ -0,0 +1,31 @@
+// scripts/extension_rule.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+// usage: ts-node scripts/extension_rule.ts <thresholdSol> <windowSecs> <stepSecs> <maxExtensionSecs>
+// (threshold 0 turns the rule off)
+async function setExtensionRule(args: string[]) {
+  if (args.length !== 4) {
+    throw new Error(
+      "usage: extension_rule.ts <thresholdSol> <windowSecs> <stepSecs> <maxExtensionSecs>"
+    );
+  }
+  const owner = anchor.getProvider().wallet.publicKey;
+  const threshold = new anchor.BN(Math.floor(Number(args[0]) * 1e9));
+  const [window, step, maxExtension] = args.slice(1).map(Number);
+
+  const tx = await (program.methods as any)
+    .adminSetExtensionRule(threshold, window, step, maxExtension)
+    .accounts({
+      owner,
+      presaleState: presaleStatePda(),
+    })
+    .rpc();
+
+  console.log("✅ Extension rule updated. Tx:", tx);
+}
+
+setExtensionRule(process.argv.slice(2)).catch(console.error);
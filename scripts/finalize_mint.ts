@@ -0,0 +1,43 @@
+// scripts/finalize_mint.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { provider, program, presaleStatePda, mintAuthPda, ataFor, tokenProgramId } from "./common";
+
+dotenv.config();
+
+async function finalizeMint(recipientArg?: string) {
+  const owner = provider.wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const mintAuth = mintAuthPda();
+  const mint = new PublicKey(process.env.MINT_ADDRESS!);
+  // reserve recipient must be the owner or an allowlisted payout destination
+  const reserveRecipient = recipientArg ? new PublicKey(recipientArg) : owner;
+
+  const presaleTokenAccount = ataFor(mint, presaleState);
+  const reserveTokenAccount = ataFor(mint, reserveRecipient);
+
+  console.log("Owner:", owner.toBase58());
+  console.log("Mint:", mint.toBase58());
+  console.log("Reserve recipient:", reserveRecipient.toBase58());
+
+  const tx = await (program.methods as any)
+    .finalizeMint()
+    .accounts({
+      owner,
+      presaleState,
+      mintAuth,
+      mint,
+      presaleTokenAccount,
+      reserveRecipient,
+      reserveTokenAccount,
+      tokenProgram: tokenProgramId(),
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  console.log("✅ Mint finalized (authority revoked). Tx:", tx);
+}
+
+finalizeMint(process.argv[2]).catch(console.error);
@@ -0,0 +1,24 @@
+// scripts/finalize_sale.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+async function finalizeSale() {
+  const owner = anchor.getProvider().wallet.publicKey;
+  const presaleState = presaleStatePda();
+
+  // only possible after presale_end or a sell-out; locks times, ladder and price for good
+  const tx = await (program.methods as any)
+    .finalize()
+    .accounts({
+      owner,
+      presaleState,
+    })
+    .rpc();
+
+  console.log("✅ Sale finalized. Tx:", tx);
+}
+
+finalizeSale().catch(console.error);
@@ -0,0 +1,55 @@
+// scripts/finalize_unsold.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { provider, program, presaleStatePda, mintAuthPda, ataFor, tokenProgramId } from "./common";
+
+dotenv.config();
+
+const ACTIONS: Record<string, object> = {
+  burn: { burn: {} },
+  reserve: { sendToReserve: {} },
+  liquidity: { rollToLiquidity: {} },
+};
+
+async function finalizeUnsold(actionArg: string, destinationArg?: string) {
+  const owner = provider.wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const mintAuth = mintAuthPda();
+  const mint = new PublicKey(process.env.MINT_ADDRESS!);
+  // destination must be the owner or an allowlisted payout destination (none for burn)
+  const destination =
+    actionArg === "burn" ? null : destinationArg ? new PublicKey(destinationArg) : owner;
+
+  const presaleTokenAccount = ataFor(mint, presaleState);
+  const destinationTokenAccount = destination && ataFor(mint, destination);
+
+  console.log("Owner:", owner.toBase58());
+  console.log("Action:", actionArg);
+  if (destination) console.log("Destination:", destination.toBase58());
+
+  const tx = await (program.methods as any)
+    .finalizeUnsold(ACTIONS[actionArg])
+    .accounts({
+      owner,
+      presaleState,
+      mintAuth,
+      mint,
+      presaleTokenAccount,
+      destination,
+      destinationTokenAccount,
+      tokenProgram: tokenProgramId(),
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+  console.log("✅ Unsold inventory finalized. Tx:", tx);
+}
+
+const actionArg = process.argv[2];
+if (!actionArg || !ACTIONS[actionArg]) {
+  console.error("Usage: node scripts/finalize_unsold.ts <burn|reserve|liquidity> [destinationPubkey]");
+  process.exit(1);
+}
+finalizeUnsold(actionArg, process.argv[3]).catch(console.error);
@@ -20,38 +20,44 @@ anchor.setProvider(anchor.AnchorProvider.env());
 
   const currentStage = get(state, ["current_stage", "currentStage"]);
   const usdPerSol = get(state, ["usd_per_sol", "usdPerSol"]);
-  const stage1Sold = get(state, ["stage_1_sold", "stage1Sold"]);
-  const stage2Sold = get(state, ["stage_2_sold", "stage2Sold"]);
-  const stage3Sold = get(state, ["stage_3_sold", "stage3Sold"]);
-  const stage4Sold = get(state, ["stage_4_sold", "stage4Sold"]);
-  const stage5Sold = get(state, ["stage_5_sold", "stage5Sold"]);
+  const stageCount = Number(get(state, ["stage_count", "stageCount"]) ?? 0);
+  const stages = state.stages.slice(0, stageCount);
+
+  // addresses as recorded on-chain (no client-side derivation needed)
+  const info = await (program.methods as any)
+    .getPresaleInfo()
+    .accounts({ presaleState })
+    .view();
 
   console.log("📊 GrowSol Presale State:");
+  console.log("Status:", Object.keys(info.status)[0]);
+  console.log("Pricing:", Object.keys(info.pricingMode)[0]);
+  if (info.pricingMode.linearCurve) {
+    // micro-cents per token -> USD
+    console.log("Curve Start Price (USD):", Number(info.curveStartPrice) / 1e8);
+    console.log("Curve End Price (USD):", Number(info.curveEndPrice) / 1e8);
+  }
+  if (info.pricingMode.auction || info.pricingMode.proRata) {
+    console.log("Clearing Price (USD):", Number(info.clearingPrice) / 1e8 || "not settled");
+  }
+  if (Number(info.bonusPoolCap) > 0) {
+    console.log("Bonus Pool (raw):", info.bonusAllocated.toString(), "/", info.bonusPoolCap.toString());
+  }
+  console.log("Mint:", info.mint.toBase58());
+  console.log("Vault:", info.vault.toBase58());
+  console.log("Treasury:", info.treasury.toBase58());
   console.log("Current Stage:", currentStage?.toString?.() ?? currentStage);
   console.log("USD Per SOL:", usdPerSol?.toString?.() ?? usdPerSol);
-  console.log("Stage 1 Sold:", stage1Sold?.toString?.() ?? stage1Sold);
-  console.log("Stage 2 Sold:", stage2Sold?.toString?.() ?? stage2Sold);
-  console.log("Stage 3 Sold:", stage3Sold?.toString?.() ?? stage3Sold);
-  console.log("Stage 4 Sold:", stage4Sold?.toString?.() ?? stage4Sold);
-  console.log("Stage 5 Sold:", stage5Sold?.toString?.() ?? stage5Sold);
-
-  // Print human readable table (assumes the stage prices are the known ladder)
-  const stagePrices = [0.01, 0.02, 0.03, 0.04, 0.05];
-  const stageSoldArr = [
-    Number(stage1Sold ?? 0),
-    Number(stage2Sold ?? 0),
-    Number(stage3Sold ?? 0),
-    Number(stage4Sold ?? 0),
-    Number(stage5Sold ?? 0),
-  ];
-
-  console.log("\nStage | Price (USD) | Tokens Sold (raw) | Capital Raised (USD)");
-  for (let i = 0; i < 5; i++) {
-    // stageSoldArr is raw token units (multiplied by 1e9). Convert to token units:
-    const tokensSoldUnits = stageSoldArr[i] / 1e9;
-    const capitalRaised = tokensSoldUnits * stagePrices[i];
+  console.log("Stage Count:", stageCount);
+
+  console.log("\nStage | Price (USD) | Cap (raw) | Tokens Sold (raw) | Capital Raised (USD)");
+  stages.forEach((stage: any, i: number) => {
+    // price is in cents, sold is raw token units (multiplied by 1e9)
+    const price = Number(stage.price) / 100;
+    const tokensSoldUnits = Number(stage.sold) / 1e9;
+    const capitalRaised = tokensSoldUnits * price;
     console.log(
-      `${i + 1}     | ${stagePrices[i]}       | ${tokensSoldUnits}        | ${capitalRaised}`
+      `${i + 1}     | ${price}       | ${stage.cap.toString()} | ${tokensSoldUnits}        | ${capitalRaised}`
     );
-  }
+  });
 })();
@@ -2,7 +2,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
 import dotenv from "dotenv";
-import { program, provider, presaleConfigPda, presaleStatePda } from "./common";
+import { program, provider, presaleConfigPda, presaleStatePda, tokenProgramId } from "./common";
 
 dotenv.config();
 
@@ -38,15 +38,36 @@ async function initialize() {
   const startTsBN = new anchor.BN(Number(process.argv[3]) || now);
   const endTsBN = new anchor.BN(Number(process.argv[4]) || now + 7 * 24 * 3600);
 
+  // supply in whole tokens (converted to raw units with 9 decimals); sale caps total 1,000,000,000
+  const TOKEN_BASE = new anchor.BN(1_000_000_000);
+  const maxSupplyTokens = Number(process.argv[5]) || 1_000_000_000;
+  const reservedTokens = Number(process.argv[6]) || 0;
+  const maxSupplyBN = new anchor.BN(maxSupplyTokens).mul(TOKEN_BASE);
+  const reservedSupplyBN = new anchor.BN(reservedTokens).mul(TOKEN_BASE);
+
   console.log(
     `Using usd_per_sol=${usdPerSolNumber}, start=${startTsBN.toString()}, end=${endTsBN.toString()}`
   );
+  console.log(`Max supply=${maxSupplyTokens} tokens, reserved=${reservedTokens} tokens`);
+
+  // optional linear bonding curve: <startUsd> <endUsd> <supplyTokens>; prices are sent as
+  // micro-cents per token (1 USD = 10^8)
+  const curve = process.argv[7]
+    ? {
+        startPrice: new anchor.BN(Math.round(Number(process.argv[7]) * 1e8)),
+        endPrice: new anchor.BN(Math.round(Number(process.argv[8]) * 1e8)),
+        supply: new anchor.BN(Number(process.argv[9])),
+      }
+    : null;
 
   const tx = await (program.methods as any)
     .initialize(
       new anchor.BN(usdPerSolNumber),
       startTsBN,
-      endTsBN
+      endTsBN,
+      maxSupplyBN,
+      reservedSupplyBN,
+      curve
     )
     .accounts({
       owner,
@@ -55,7 +76,7 @@ async function initialize() {
       mint: mint.publicKey,
       mintAuth: mintAuthPda,
       treasury: treasuryPda,
-      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenProgram: tokenProgramId(),
       systemProgram: SystemProgram.programId,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
     })
@@ -66,6 +87,13 @@ async function initialize() {
   console.log("\nIMPORTANT: save this mint address to your .env as MINT_ADDRESS:");
   console.log("MINT_ADDRESS=" + mint.publicKey.toBase58());
 
+  if (curve) {
+    console.log(
+      `\nBonding curve: $${process.argv[7]} -> $${process.argv[8]} over ${process.argv[9]} tokens`
+    );
+    return;
+  }
+
   console.log("\nPresale Stage Setup:");
   console.log("Stage | Price (USD) | Cap (Tokens)");
   console.log("1     | 0.01        | 150,000,000");
@@ -0,0 +1,46 @@
+// scripts/migrate.ts
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { program, presaleStatePda, tokenProgramId, userAllocationPdaFor } from "./common";
+
+dotenv.config();
+
+// usage: ts-node scripts/migrate.ts state        (owner: presale state; MINT_ADDRESS is the
+//                                               sale mint, which pre-versioning states never
+//                                               recorded)
+//        ts-node scripts/migrate.ts allocation   (buyer: own allocation)
+async function migrate(target: string) {
+  const signer = anchor.getProvider().wallet.publicKey;
+  const presaleState = presaleStatePda();
+
+  let tx: string;
+  if (target === "state") {
+    tx = await (program.methods as any)
+      .migratePresaleState()
+      .accounts({
+        owner: signer,
+        presaleState,
+        mint: new PublicKey(process.env.MINT_ADDRESS!),
+        tokenProgram: tokenProgramId(),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  } else if (target === "allocation") {
+    tx = await (program.methods as any)
+      .migrateUserAllocation()
+      .accounts({
+        buyer: signer,
+        presaleState,
+        userAllocation: userAllocationPdaFor(signer),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+  } else {
+    throw new Error("usage: migrate.ts <state|allocation>");
+  }
+
+  console.log(`✅ Migrated ${target}. Tx:`, tx);
+}
+
+migrate(process.argv[2]).catch(console.error);
@@ -0,0 +1,40 @@
+// scripts/payout_destinations.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+async function updateAllowlist(cmd: "add" | "remove", destination: PublicKey) {
+  const owner = anchor.getProvider().wallet.publicKey;
+  const presaleState = presaleStatePda();
+
+  if (cmd === "add") {
+    const tx = await (program.methods as any)
+      .addPayoutDestination(destination)
+      .accounts({
+        owner,
+        presaleState,
+      })
+      .rpc();
+    console.log("Allowlisted", destination.toBase58(), "Tx:", tx);
+  } else {
+    const tx = await (program.methods as any)
+      .removePayoutDestination(destination)
+      .accounts({
+        owner,
+        presaleState,
+      })
+      .rpc();
+    console.log("Removed", destination.toBase58(), "Tx:", tx);
+  }
+}
+
+const cmd = process.argv[2] as "add" | "remove";
+const destinationArg = process.argv[3];
+if (!cmd || (cmd !== "add" && cmd !== "remove") || !destinationArg) {
+  console.error("Usage: node scripts/payout_destinations.ts <add|remove> <destinationPubkey>");
+  process.exit(1);
+}
+updateAllowlist(cmd, new PublicKey(destinationArg)).catch(console.error);
@@ -0,0 +1,29 @@
+// scripts/rate_limits.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+// usage: ts-node scripts/rate_limits.ts <cooldownSecs> <slotCapRaw>
+// (0 turns either limit off; the slot cap is in raw token units)
+async function setRateLimits(args: string[]) {
+  if (args.length !== 2) {
+    throw new Error("usage: rate_limits.ts <cooldownSecs> <slotCapRaw>");
+  }
+  const owner = anchor.getProvider().wallet.publicKey;
+  const cooldown = Number(args[0]);
+  const slotCap = new anchor.BN(args[1]);
+
+  const tx = await (program.methods as any)
+    .adminSetRateLimits(cooldown, slotCap)
+    .accounts({
+      owner,
+      presaleState: presaleStatePda(),
+    })
+    .rpc();
+
+  console.log("✅ Rate limits updated. Tx:", tx);
+}
+
+setRateLimits(process.argv.slice(2)).catch(console.error);
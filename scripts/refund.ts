@@ -0,0 +1,38 @@
+// scripts/refund.ts
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import {
+  provider,
+  program,
+  presaleStatePda,
+  treasuryPda,
+  auctionBookPda,
+  userAllocationPdaFor,
+} from "./common";
+
+dotenv.config();
+
+async function refund() {
+  const buyer = anchor.getProvider().wallet.publicKey;
+  const presaleState = presaleStatePda();
+  // a settled auction bid that was never claimed needs the book to undo its fill
+  const auctionBook = (await provider.connection.getAccountInfo(auctionBookPda()))
+    ? auctionBookPda()
+    : null;
+
+  // returns the lamports paid and closes the allocation back to the buyer
+  const tx = await (program.methods as any)
+    .refund()
+    .accounts({
+      buyer,
+      presaleState,
+      treasury: treasuryPda(),
+      auctionBook,
+      userAllocation: userAllocationPdaFor(buyer),
+    })
+    .rpc();
+
+  console.log("✅ Refunded. Tx:", tx);
+}
+
+refund().catch(console.error);
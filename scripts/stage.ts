@@ -0,0 +1,39 @@
+// scripts/stage.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+// usage:
+//   ts-node scripts/stage.ts set <stage> <priceCents> <capRaw>
+//   ts-node scripts/stage.ts insert <stage> <priceCents> <capRaw>
+//   ts-node scripts/stage.ts remove <stage>
+async function editStage(args: string[]) {
+  const [edit, stageArg, price, cap] = args;
+  const stage = Number(stageArg);
+  const owner = anchor.getProvider().wallet.publicKey;
+  const methods = program.methods as any;
+
+  let builder;
+  if (edit === "set" && cap !== undefined) {
+    builder = methods.adminSetStage(stage, new anchor.BN(price), new anchor.BN(cap));
+  } else if (edit === "insert" && cap !== undefined) {
+    builder = methods.adminInsertStage(stage, new anchor.BN(price), new anchor.BN(cap));
+  } else if (edit === "remove" && stageArg !== undefined) {
+    builder = methods.adminRemoveStage(stage);
+  } else {
+    throw new Error("usage: stage.ts <set|insert> <stage> <priceCents> <capRaw> | remove <stage>");
+  }
+
+  const tx = await builder
+    .accounts({
+      owner,
+      presaleState: presaleStatePda(),
+    })
+    .rpc();
+
+  console.log(`✅ Stage ${stage} ${edit} done. Tx:`, tx);
+}
+
+editStage(process.argv.slice(2)).catch(console.error);
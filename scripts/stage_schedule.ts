@@ -0,0 +1,29 @@
+// scripts/stage_schedule.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import dotenv from "dotenv";
+import { program, presaleStatePda } from "./common";
+
+dotenv.config();
+
+// usage: ts-node scripts/stage_schedule.ts <stage2> ... <stageN> <roll|discard>
+// (one start per stage after the first)
+async function setStageSchedule(args: string[]) {
+  if (args.length < 1) {
+    throw new Error("usage: stage_schedule.ts <stage2> ... <stageN> <roll|discard>");
+  }
+  const owner = anchor.getProvider().wallet.publicKey;
+  const starts = args.slice(0, -1).map((s) => new anchor.BN(s));
+  const policy = args[args.length - 1] === "roll" ? { rollForward: {} } : { discard: {} };
+
+  const tx = await (program.methods as any)
+    .adminSetStageSchedule(starts, policy)
+    .accounts({
+      owner,
+      presaleState: presaleStatePda(),
+    })
+    .rpc();
+
+  console.log("✅ Stage schedule updated. Tx:", tx);
+}
+
+setStageSchedule(process.argv.slice(2)).catch(console.error);
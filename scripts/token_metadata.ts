@@ -0,0 +1,55 @@
+// scripts/token_metadata.ts // FOR OWNERS ONLY
+import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import dotenv from "dotenv";
+import { provider, program, presaleStatePda, mintAuthPda } from "./common";
+
+dotenv.config();
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+async function setMetadata(cmd: "create" | "update", name: string, symbol: string, uri: string) {
+  const owner = provider.wallet.publicKey;
+  const presaleState = presaleStatePda();
+  const mintAuth = mintAuthPda();
+  const mint = new PublicKey(process.env.MINT_ADDRESS!);
+
+  const [metadata] = PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+
+  console.log("Mint:", mint.toBase58());
+  console.log("Metadata PDA:", metadata.toBase58());
+  console.log(`Name=${name} Symbol=${symbol} URI=${uri}`);
+
+  const accounts = {
+    owner,
+    presaleState,
+    mintAuth,
+    mint,
+    metadata,
+    tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+  };
+
+  const tx =
+    cmd === "create"
+      ? await (program.methods as any)
+          .createTokenMetadata(name, symbol, uri)
+          .accounts({
+            ...accounts,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .rpc()
+      : await (program.methods as any).updateTokenMetadata(name, symbol, uri).accounts(accounts).rpc();
+
+  console.log(`✅ Metadata ${cmd}d. Tx:`, tx);
+}
+
+const [cmd, name, symbol, uri] = process.argv.slice(2) as ["create" | "update", string, string, string];
+if ((cmd !== "create" && cmd !== "update") || !name || !symbol || !uri) {
+  console.error("Usage: node scripts/token_metadata.ts <create|update> <name> <symbol> <uri>");
+  process.exit(1);
+}
+setMetadata(cmd, name, symbol, uri).catch(console.error);
@@ -1,5 +1,6 @@
 // scripts/withdraw_sol.ts // FOR OWNERS ONLY
 import * as anchor from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
 import dotenv from "dotenv";
 import {
   provider,
@@ -10,8 +11,11 @@ import {
 
 dotenv.config();
 
-async function withdraw(solAmount: number) {
+// solAmount = 0 withdraws everything above the treasury's rent-exempt minimum
+async function withdraw(solAmount: number, recipientArg?: string) {
   const owner = provider.wallet.publicKey;
+  // recipient must be the owner or an allowlisted payout destination
+  const recipient = recipientArg ? new PublicKey(recipientArg) : owner;
   const presaleState = presaleStatePda();
   const treasury = treasuryPda();
 
@@ -21,14 +25,24 @@ async function withdraw(solAmount: number) {
   console.log("Owner:", owner.toBase58());
   console.log("Presale State PDA:", presaleState.toBase58());
   console.log("Treasury PDA:", treasury.toBase58());
-  console.log(`Withdrawing: ${solAmount} SOL (${lamports} lamports)`);
-
-  const tx = await (program.methods as any)
-    .withdrawSol(new anchor.BN(lamports))
+  console.log("Recipient:", recipient.toBase58());
+  if (lamports > 0) {
+    console.log(`Withdrawing: ${solAmount} SOL (${lamports} lamports)`);
+  } else {
+    console.log("Withdrawing: full treasury balance (keeping rent-exempt minimum)");
+  }
+
+  const method =
+    lamports > 0
+      ? (program.methods as any).withdrawSol(new anchor.BN(lamports))
+      : (program.methods as any).withdrawAllSol();
+
+  const tx = await method
     .accounts({
       owner,
       presaleState,
       treasury,
+      recipient,
       systemProgram: anchor.web3.SystemProgram.programId, // still required in context
     })
     .rpc();
@@ -36,10 +50,10 @@ async function withdraw(solAmount: number) {
   console.log("\n✅ Withdraw transaction signature:", tx);
 }
 
-const solAmount = Number(process.argv[2]);
-if (!solAmount || solAmount <= 0) {
-  console.error("❌ Please specify a valid SOL amount, e.g.\nnode scripts/withdraw_sol.ts 1.5");
+const solAmount = Number(process.argv[2] ?? 0);
+if (Number.isNaN(solAmount) || solAmount < 0) {
+  console.error("❌ Please specify a valid SOL amount (0 or omitted = all), e.g.\nnode scripts/withdraw_sol.ts 1.5 [recipientPubkey]");
   process.exit(1);
 }
 
-withdraw(solAmount).catch(console.error);
+withdraw(solAmount, process.argv[3]).catch(console.error);
@@ -2,28 +2,28 @@
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
 import dotenv from "dotenv";
-import { provider, program, presaleStatePda, mintAuthPda } from "./common";
+import { provider, program, presaleStatePda, mintAuthPda, ataFor, tokenProgramId } from "./common";
 
 dotenv.config();
 
-async function withdrawToken(amountRaw: number) {
+async function withdrawToken(amountRaw: number, recipientArg?: string) {
   const owner = provider.wallet.publicKey;
+  // recipient must be the owner or an allowlisted payout destination
+  const recipient = recipientArg ? new PublicKey(recipientArg) : owner;
   const presaleState = presaleStatePda();
   const mintAuth = mintAuthPda();
   const mint = new PublicKey(process.env.MINT_ADDRESS!);
 
-  // Owner ATA for mint (will be created if missing)
-  const ownerAta = await anchor.utils.token.associatedAddress({ mint, owner });
+  // Recipient ATA for mint (will be created if missing)
+  const recipientAta = ataFor(mint, recipient);
 
   // Presale vault (ATA of presale_state PDA holding tokens)
-  const presaleTokenAccount = await anchor.utils.token.associatedAddress({
-    mint,
-    owner: presaleState, // presale_state PDA owns the vault
-  });
+  const presaleTokenAccount = ataFor(mint, presaleState);
 
   console.log("Owner:", owner.toBase58());
   console.log("Mint:", mint.toBase58());
-  console.log("Owner ATA:", ownerAta.toBase58());
+  console.log("Recipient:", recipient.toBase58());
+  console.log("Recipient ATA:", recipientAta.toBase58());
   console.log("Presale token ATA:", presaleTokenAccount.toBase58());
   console.log("Amount (raw units):", amountRaw);
 
@@ -35,8 +35,9 @@ async function withdrawToken(amountRaw: number) {
       presaleTokenAccount,       // <-- must provide now
       mintAuth,
       mint,
-      ownerTokenAccount: ownerAta,
-      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      recipient,
+      recipientTokenAccount: recipientAta,
+      tokenProgram: tokenProgramId(),
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
@@ -47,7 +48,7 @@ async function withdrawToken(amountRaw: number) {
 
 const amtRaw = Number(process.argv[2]) || 0;
 if (!amtRaw || amtRaw <= 0) {
-  console.error("Usage: node scripts/withdraw_token.ts <amount_raw>");
+  console.error("Usage: node scripts/withdraw_token.ts <amount_raw> [recipientPubkey]");
   process.exit(1);
 }
-withdrawToken(amtRaw).catch(console.error);
+withdrawToken(amtRaw, process.argv[3]).catch(console.error);
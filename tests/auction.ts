@@ -0,0 +1,124 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, expectError } from "./harness";
+
+/** One US cent in the micro-cents auction prices are quoted in. */
+const CENT = 1_000_000;
+const USD_PER_SOL = 100n;
+
+/** Raw units `lamports` buy at `price`, rounded down like the program. */
+const rawFor = (lamports: bigint, price: number) =>
+  ((lamports * USD_PER_SOL * 100n * BigInt(CENT)) / BigInt(SOL)) * TOKEN / BigInt(price);
+
+/** Lamports paying for `raw` units at `price`, rounded up like the program. */
+const costOf = (raw: bigint, price: number) => {
+  const denominator = TOKEN * USD_PER_SOL * 100n * BigInt(CENT);
+  return (raw * BigInt(price) * BigInt(SOL) + denominator - 1n) / denominator;
+};
+
+describe("Uniform-price auction", () => {
+  // 10k tokens; bids at 3c (1 SOL), 2c (2 SOL + 1 SOL) and the 1c floor (1 SOL)
+  const SUPPLY = 10_000;
+  const CLEARING = 2 * CENT;
+  let sale: Sale;
+  let high: Keypair;
+  let marginal: Keypair;
+  let marginalSmall: Keypair;
+  let outbid: Keypair;
+
+  /** Claim and return what left the treasury. */
+  async function claimRefund(bidder: Keypair): Promise<number> {
+    const before = await sale.lamports(sale.treasury);
+    await sale.claim(bidder, false, sale.auctionBook);
+    return before - (await sale.lamports(sale.treasury));
+  }
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init({ startIn: 60 });
+    await sale.configureAuction(CENT, CENT, SUPPLY);
+    [high, marginal, marginalSmall, outbid] = [0, 1, 2, 3].map(() => sale.fundedWallet());
+    await sale.warpTo((await sale.state()).presaleStart.toNumber() + 10);
+  });
+
+  it("Takes bids on the price grid", async () => {
+    await expectError(sale.commitBid(outbid, SOL, CENT - 1), "BidBelowFloor");
+
+    await sale.commitBid(high, SOL, 3 * CENT);
+    await expectError(sale.commitBid(high, SOL / 2, 2 * CENT), "BidPriceMismatch");
+    await sale.commitBid(marginal, SOL, 2 * CENT);
+    await sale.commitBid(marginal, SOL + 1, 2 * CENT + 1); // rounds down into the 2c bucket
+    await sale.commitBid(marginalSmall, SOL, 2 * CENT);
+    await sale.commitBid(outbid, SOL, CENT);
+
+    const state = await sale.state();
+    expect(state.totalCommittedLamports.toNumber()).to.equal(5 * SOL + 1);
+    expect(state.refundsOwedLamports.toNumber()).to.equal(5 * SOL + 1);
+  });
+
+  it("Settles only after bidding closes", async () => {
+    await expectError(sale.settle(), "SaleNotEnded");
+    await sale.endSale();
+    await expectError(sale.claim(high, false, sale.auctionBook), "AuctionNotSettled");
+  });
+
+  it("Clears in the bucket where demand covers the supply and fills it partially", async () => {
+    await sale.settle();
+
+    const supplyRaw = BigInt(SUPPLY) * TOKEN;
+    const aboveRaw = rawFor(BigInt(SOL), CLEARING);
+    const state = await sale.state();
+    expect(state.clearingPrice.toNumber()).to.equal(CLEARING);
+    expect(state.marginalBucket).to.equal(1);
+    expect(state.marginalFillRaw.toString()).to.equal((supplyRaw - aboveRaw).toString());
+    expect(state.totalAllocated.toString()).to.equal(supplyRaw.toString());
+
+    const proceeds = costOf(supplyRaw, CLEARING);
+    expect(state.refundsOwedLamports.toString()).to.equal(
+      (BigInt(5 * SOL + 1) - proceeds).toString()
+    );
+    await expectError(sale.settle(), "AuctionAlreadySettled");
+  });
+
+  it("Fills bids above the marginal bucket in full at the clearing price", async () => {
+    expect(await claimRefund(high)).to.equal(0);
+    expect(await sale.tokenBalance(high.publicKey)).to.equal(rawFor(BigInt(SOL), CLEARING));
+  });
+
+  it("Shares the marginal bucket pro rata and refunds the rest from the treasury", async () => {
+    const state = await sale.state();
+    const fill = BigInt(state.marginalFillRaw.toString());
+    const bucket = BigInt(3 * SOL + 1);
+
+    for (const [bidder, committed] of [
+      [marginal, BigInt(2 * SOL + 1)],
+      [marginalSmall, BigInt(SOL)],
+    ] as [Keypair, bigint][]) {
+      const owedBefore = BigInt((await sale.state()).refundsOwedLamports.toString());
+      const raw = (fill * committed) / bucket;
+      const refund = committed - costOf(raw, CLEARING);
+
+      expect(BigInt(await claimRefund(bidder))).to.equal(refund);
+      expect(await sale.tokenBalance(bidder.publicKey)).to.equal(raw);
+      const alloc = await sale.allocation(bidder.publicKey);
+      expect(alloc.paidLamports.toString()).to.equal((committed - refund).toString());
+      expect(alloc.committedLamports.toNumber()).to.equal(0);
+      expect((await sale.state()).refundsOwedLamports.toString()).to.equal(
+        (owedBefore - refund).toString()
+      );
+    }
+  });
+
+  it("Refunds a bid below the clearing price in full", async () => {
+    const owedBefore = (await sale.state()).refundsOwedLamports.toNumber();
+
+    expect(await claimRefund(outbid)).to.equal(SOL);
+    expect(await sale.tokenBalance(outbid.publicKey)).to.equal(0n);
+    expect((await sale.allocation(outbid.publicKey)).allocatedRaw.toNumber()).to.equal(0);
+    // only rounding dust of the marginal fills is left owed
+    const owed = (await sale.state()).refundsOwedLamports.toNumber();
+    expect(owed).to.equal(owedBefore - SOL);
+    expect(owed).to.be.lessThan(3);
+  });
+});
@@ -0,0 +1,60 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, bn, expectError } from "./harness";
+
+describe("Cumulative volume bonus", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    // room for a 10M token bonus pool next to the 1B token default ladder
+    await sale.init({ maxSupply: 1_010_000_000n * TOKEN });
+    await sale.methods
+      .adminSetBonus(
+        0,
+        0,
+        [{ minUsdCents: bn(15_000), bps: 1_000 }], // +10% once a wallet has spent $150
+        { cumulative: {} },
+        bn(10_000_000n * TOKEN)
+      )
+      .accounts({ owner: sale.owner.publicKey, presaleState: sale.presaleState })
+      .rpc();
+    buyer = sale.fundedWallet();
+  });
+
+  it("Pays the tier once the running total reaches it", async () => {
+    // 1 SOL = $100: below the tier
+    await sale.buy(buyer, SOL);
+    const first = await sale.allocation(buyer.publicKey);
+    expect(first.purchasedUsdCents.toNumber()).to.equal(10_000);
+    expect(first.bonusRaw.toNumber()).to.equal(0);
+
+    // another $60 takes the wallet to $160
+    await sale.buy(buyer, (SOL * 6) / 10);
+    const second = await sale.allocation(buyer.publicKey);
+    expect(second.purchasedUsdCents.toNumber()).to.equal(16_000);
+    const base = second.allocatedRaw.sub(first.allocatedRaw);
+    expect(second.bonusRaw.toString()).to.equal(base.divn(10).toString());
+  });
+
+  it("Keeps the running total through a claim", async () => {
+    // the allocation cannot be claimed and closed mid-sale to start the total over
+    await expectError(sale.claim(buyer, true), "SaleNotEnded");
+
+    await sale.endSale();
+    const before = await sale.allocation(buyer.publicKey);
+    await sale.claim(buyer);
+
+    const after = await sale.allocation(buyer.publicKey);
+    expect(after.purchasedUsdCents.toNumber()).to.equal(16_000);
+    expect(after.bonusRaw.toString()).to.equal(before.bonusRaw.toString());
+    expect(after.claimedRaw.toString()).to.equal(
+      before.allocatedRaw.add(before.bonusRaw).toString()
+    );
+    expect((await sale.tokenBalance(buyer.publicKey)).toString()).to.equal(
+      after.claimedRaw.toString()
+    );
+  });
+});
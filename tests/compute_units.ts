@@ -0,0 +1,69 @@
+// Compute units of the hot instructions, measured on a bank and compared with the figures
+// recorded in compute_units.json for the Borsh-serialized PresaleState (the last commit
+// before the zero-copy layout). `CU_RECORD=<label>` writes this run's figures under
+// <label> instead, e.g. `CU_RECORD=borsh` against a build of that commit.
+import * as fs from "fs";
+import * as path from "path";
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale } from "./harness";
+import baselines from "./compute_units.json";
+
+type Measured = "buyTokens" | "buyTokensAgain" | "claimTokens";
+
+/** The default per-transaction compute budget none of them may come near. */
+const BUDGET = 200_000;
+
+describe("Compute units", () => {
+  const record = process.env.CU_RECORD;
+  const measured: Partial<Record<Measured, number>> = {};
+  let sale: Sale;
+  let buyer: Keypair;
+
+  /** Check `units` against the recorded Borsh figure (or just keep it when recording). */
+  function compare(this: Mocha.Context, name: Measured, units: number) {
+    console.log(`    ⛽ ${name}: ${units} compute units`);
+    measured[name] = units;
+    expect(units).to.be.greaterThan(0).and.lessThan(BUDGET);
+    if (record) return;
+
+    const baseline = (baselines.borsh as Record<string, unknown>)[name] as number | null;
+    if (baseline == null) this.skip(); // nothing recorded to compare against yet
+    expect(units, `${name} against the Borsh layout`).to.be.at.most(baseline!);
+  }
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    buyer = sale.fundedWallet();
+  });
+
+  after(() => {
+    if (!record) return;
+    const file = path.join(__dirname, "compute_units.json");
+    const recorded = JSON.parse(fs.readFileSync(file, "utf8"));
+    recorded[record] = { ...recorded[record], ...measured };
+    fs.writeFileSync(file, JSON.stringify(recorded, null, 2) + "\n");
+  });
+
+  it("buy_tokens opening an allocation", async function () {
+    const units = await sale.computeUnits(sale.buyBuilder(buyer, SOL), [buyer]);
+    await sale.buy(buyer, SOL);
+    compare.call(this, "buyTokens", units);
+  });
+
+  it("buy_tokens adding to an allocation", async function () {
+    await sale.warpBy(1);
+    const units = await sale.computeUnits(sale.buyBuilder(buyer, SOL / 2), [buyer]);
+    await sale.buy(buyer, SOL / 2);
+    compare.call(this, "buyTokensAgain", units);
+  });
+
+  it("claim_tokens", async function () {
+    await sale.endSale();
+    const units = await sale.computeUnits(sale.claimBuilder(buyer), [buyer]);
+    await sale.claim(buyer);
+    compare.call(this, "claimTokens", units);
+  });
+});
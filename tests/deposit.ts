@@ -0,0 +1,68 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, bn, expectError } from "./harness";
+
+// the default ladder sells 1B tokens
+const SALE_SUPPLY = 1_000_000_000n * TOKEN;
+
+describe("Pre-funded vault sale", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.createMint({ supply: 2n * SALE_SUPPLY });
+    await sale.initWithMint();
+    buyer = sale.fundedWallet();
+  });
+
+  it("Rejects a mint too small for the ladder", async () => {
+    const small = await Sale.start();
+    await small.createMint({ supply: SALE_SUPPLY - 1n });
+    await expectError(small.initWithMint(), "InvalidSupplyConfig");
+  });
+
+  it("Does not sell before the whole inventory is deposited", async () => {
+    await sale.depositTokens(SALE_SUPPLY - TOKEN);
+    await expectError(sale.buy(buyer, SOL), "InsufficientInventory");
+
+    await sale.depositTokens(TOKEN);
+    const state = await sale.state();
+    expect(state.totalDeposited.toString()).to.equal(SALE_SUPPLY.toString());
+    await sale.buy(buyer, SOL);
+  });
+
+  it("Rejects a bonus pool the deposits do not cover", async () => {
+    const setPool = (poolRaw: bigint) =>
+      sale.methods
+        .adminSetBonus(1_000, 3_600, [], { perPurchase: {} }, bn(poolRaw))
+        .accounts({ owner: sale.owner.publicKey, presaleState: sale.presaleState })
+        .rpc();
+
+    await expectError(setPool(1_000n * TOKEN), "InsufficientInventory");
+    await sale.depositTokens(1_000n * TOKEN);
+    await setPool(1_000n * TOKEN);
+  });
+
+  it("Keeps the sale inventory in the vault while the sale runs", async () => {
+    await sale.depositTokens(500n * TOKEN);
+    await expectError(sale.withdrawToken(501n * TOKEN), "InsufficientVaultBalance");
+
+    await sale.withdrawToken(500n * TOKEN);
+    const state = await sale.state();
+    expect(state.totalDeposited.toString()).to.equal((SALE_SUPPLY + 1_000n * TOKEN).toString());
+  });
+
+  it("Pays claims out of the vault once the sale has ended", async () => {
+    await sale.endSale();
+    const vaultBefore = await sale.tokenBalance(sale.presaleState);
+    await sale.claim(buyer);
+
+    const alloc = await sale.allocation(buyer.publicKey);
+    const claimed = BigInt(alloc.claimedRaw.toString());
+    expect(claimed > 0n).to.be.true;
+    expect(await sale.tokenBalance(buyer.publicKey)).to.equal(claimed);
+    expect(await sale.tokenBalance(sale.presaleState)).to.equal(vaultBefore - claimed);
+  });
+});
@@ -0,0 +1,65 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, Status, expectError } from "./harness";
+
+describe("Sale lifecycle", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init({ startIn: 60 });
+    buyer = sale.fundedWallet();
+  });
+
+  it("Opens at the start time", async () => {
+    expect((await sale.state()).status).to.equal(Status.Pending);
+    await expectError(sale.buy(buyer, SOL), "SaleNotStarted");
+
+    await sale.warpTo((await sale.state()).presaleStart.toNumber());
+    await sale.buy(buyer, SOL);
+    expect((await sale.state()).status).to.equal(Status.Live);
+  });
+
+  it("Cannot be finalized while it is live", async () => {
+    await expectError(sale.finalize(), "SaleNotEnded");
+  });
+
+  it("Only lets the owner finalize", async () => {
+    await sale.endSale();
+    const stranger = sale.fundedWallet();
+    await expectError(
+      sale.methods
+        .finalize()
+        .accounts({ owner: stranger.publicKey, presaleState: sale.presaleState })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Finalizes an ended sale and records when", async () => {
+    await sale.finalize();
+
+    const state = await sale.state();
+    expect(state.status).to.equal(Status.Finalized);
+    expect(state.finalizedAt.toNumber()).to.equal(await sale.now());
+  });
+
+  it("Locks the sale once finalized", async () => {
+    await expectError(sale.finalize(), "SaleLocked");
+    await expectError(sale.cancel(), "SaleLocked");
+    const now = await sale.now();
+    await expectError(sale.setTimes(now, now + 3_600), "SaleLocked");
+    await expectError(sale.buy(buyer, SOL / 2), "SaleLocked");
+  });
+
+  it("Still pays out claims", async () => {
+    const alloc = await sale.allocation(buyer.publicKey);
+    await sale.claim(buyer);
+    expect((await sale.tokenBalance(buyer.publicKey)).toString()).to.equal(
+      alloc.allocatedRaw.add(alloc.bonusRaw).toString()
+    );
+  });
+});
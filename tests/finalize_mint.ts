@@ -0,0 +1,72 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, expectError } from "./harness";
+
+// the default ladder sells 1B tokens; 10M more are reserved for the team
+const SALE_SUPPLY = 1_000_000_000n * TOKEN;
+const RESERVED = 10_000_000n * TOKEN;
+
+describe("finalize_mint", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init({ maxSupply: SALE_SUPPLY + RESERVED, reservedSupply: RESERVED });
+    buyer = sale.fundedWallet();
+    await sale.buy(buyer, SOL);
+  });
+
+  it("Rejects a reserve that does not fit under the max supply", async () => {
+    const other = await Sale.start();
+    await expectError(
+      other.init({ maxSupply: SALE_SUPPLY + RESERVED - 1n, reservedSupply: RESERVED }),
+      "InvalidSupplyConfig"
+    );
+  });
+
+  it("Waits for the sale to be finalized", async () => {
+    await expectError(sale.finalizeMint(), "SaleNotFinalized");
+  });
+
+  it("Only mints the reserve to an allowlisted wallet", async () => {
+    await sale.endSale();
+    await sale.finalize();
+    await expectError(sale.finalizeMint(Keypair.generate().publicKey), "DestinationNotAllowed");
+  });
+
+  it("Pre-mints unclaimed allocations, mints the reserve and revokes the authority", async () => {
+    const alloc = await sale.allocation(buyer.publicKey);
+    const owed = BigInt(alloc.allocatedRaw.add(alloc.bonusRaw).toString());
+
+    await sale.finalizeMint();
+
+    const mint = await sale.mintInfo();
+    expect(mint.mintAuthority).to.equal(null);
+    expect(mint.supply).to.equal(owed + RESERVED);
+    expect(await sale.tokenBalance(sale.presaleState)).to.equal(owed);
+    expect(await sale.tokenBalance(sale.owner.publicKey)).to.equal(RESERVED);
+
+    const state = await sale.state();
+    expect(state.mintFinalized).to.equal(1);
+    expect(state.totalMinted.toString()).to.equal((owed + RESERVED).toString());
+    expect(state.maxSupply.toString()).to.equal((SALE_SUPPLY + RESERVED).toString());
+  });
+
+  it("Can only revoke once", async () => {
+    await expectError(sale.finalizeMint(), "MintFinalized");
+  });
+
+  it("Serves claims from the pre-minted vault", async () => {
+    const alloc = await sale.allocation(buyer.publicKey);
+    await sale.claim(buyer);
+    expect((await sale.tokenBalance(buyer.publicKey)).toString()).to.equal(
+      alloc.allocatedRaw.add(alloc.bonusRaw).toString()
+    );
+    expect(await sale.tokenBalance(sale.presaleState)).to.equal(0n);
+    expect(await sale.mintSupply()).to.equal(
+      BigInt(alloc.allocatedRaw.add(alloc.bonusRaw).toString()) + RESERVED
+    );
+  });
+});
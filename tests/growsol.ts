@@ -9,8 +9,13 @@ import {
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
   getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  transfer,
 } from "@solana/spl-token";
+import { expect } from "chai";
 
 import idl from "../target/idl/growsol.json";
 
@@ -34,12 +39,27 @@ describe("GrowSol Presale", () => {
   let presaleTokenAta: PublicKey;
 
   const buyer = Keypair.generate();
+  // signer that must never be able to touch the buyer's allocation
+  const attacker = Keypair.generate();
+
+  // runs a transaction that must be rejected by the program
+  async function expectRejected(tx: Promise<unknown>) {
+    let failed = false;
+    try {
+      await tx;
+    } catch (_err) {
+      failed = true;
+    }
+    expect(failed, "transaction should have been rejected").to.be.true;
+  }
 
   before("Airdrop SOL to participants", async () => {
     // Airdrop to owner
     await provider.connection.requestAirdrop(owner.publicKey, 5 * LAMPORTS_PER_SOL);
     // Airdrop to buyer
     await provider.connection.requestAirdrop(buyer.publicKey, 5 * LAMPORTS_PER_SOL);
+    // Airdrop to attacker (rent for any account it tries to create)
+    await provider.connection.requestAirdrop(attacker.publicKey, LAMPORTS_PER_SOL);
   });
 
   it("Derives all PDAs", async () => {
@@ -77,7 +97,10 @@ describe("GrowSol Presale", () => {
       .initialize(
         new anchor.BN(120),          // usd_per_sol
         new anchor.BN(now - 10),     // start
-        new anchor.BN(now + 5000)    // end
+        new anchor.BN(now + 5000),   // end
+        new anchor.BN("1100000000000000000"), // max_supply (1.1B tokens raw)
+        new anchor.BN("100000000000000000"),  // reserved_supply (100M tokens raw)
+        null                                  // stage ladder pricing
       )
       .accounts({
         owner: owner.publicKey,
@@ -97,6 +120,102 @@ describe("GrowSol Presale", () => {
     console.log("✅ Initialize complete");
   });
 
+  // Metaplex token metadata (cloned into the local validator, see Anchor.toml)
+  const tokenMetadataProgram = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+  function metadataAccounts(signer: PublicKey) {
+    const [metadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        tokenMetadataProgram.toBuffer(),
+        mintKeypair.publicKey.toBuffer(),
+      ],
+      tokenMetadataProgram
+    );
+    return {
+      owner: signer,
+      presaleState: presaleStatePda,
+      mintAuth: mintAuthPda,
+      mint: mintKeypair.publicKey,
+      metadata,
+      tokenMetadataProgram,
+    };
+  }
+
+  // name and symbol from a Metadata account: key, update authority and mint come first,
+  // then borsh strings padded with NULs
+  async function fetchMetadata() {
+    const { metadata } = metadataAccounts(owner.publicKey);
+    const info = await provider.connection.getAccountInfo(metadata);
+    expect(info!.owner.toBase58()).to.equal(tokenMetadataProgram.toBase58());
+    let offset = 1 + 32 + 32;
+    const readString = () => {
+      const len = info!.data.readUInt32LE(offset);
+      const value = info!.data
+        .subarray(offset + 4, offset + 4 + len)
+        .toString()
+        .replace(/\0/g, "");
+      offset += 4 + len;
+      return value;
+    };
+    const updateAuthority = new PublicKey(info!.data.subarray(1, 33));
+    return { updateAuthority, name: readString(), symbol: readString(), uri: readString() };
+  }
+
+  it("Rejects metadata created by someone other than the owner", async () => {
+    await expectRejected(
+      program.methods
+        .createTokenMetadata("GrowSol", "GROW", "https://example.com/grow.json")
+        .accounts({
+          ...metadataAccounts(attacker.publicKey),
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([attacker])
+        .rpc()
+    );
+  });
+
+  it("Owner creates the mint's metadata", async () => {
+    await program.methods
+      .createTokenMetadata("GrowSol", "GROW", "https://example.com/grow.json")
+      .accounts({
+        ...metadataAccounts(owner.publicKey),
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([owner])
+      .rpc();
+
+    const metadata = await fetchMetadata();
+    expect(metadata.updateAuthority.toBase58()).to.equal(mintAuthPda.toBase58());
+    expect(metadata.name).to.equal("GrowSol");
+    expect(metadata.symbol).to.equal("GROW");
+    expect(metadata.uri).to.equal("https://example.com/grow.json");
+  });
+
+  it("Rejects a metadata update from someone other than the owner", async () => {
+    await expectRejected(
+      program.methods
+        .updateTokenMetadata("Hijacked", "HJK", "https://example.com/hijacked.json")
+        .accounts(metadataAccounts(attacker.publicKey))
+        .signers([attacker])
+        .rpc()
+    );
+  });
+
+  it("Owner updates the mint's metadata", async () => {
+    await program.methods
+      .updateTokenMetadata("GrowSol Token", "GROW", "https://example.com/grow-v2.json")
+      .accounts(metadataAccounts(owner.publicKey))
+      .signers([owner])
+      .rpc();
+
+    const metadata = await fetchMetadata();
+    expect(metadata.name).to.equal("GrowSol Token");
+    expect(metadata.uri).to.equal("https://example.com/grow-v2.json");
+  });
+
   it("Owner sets presale times", async () => {
     const newStart = Math.floor(Date.now() / 1000);
     const newEnd = newStart + 8000;
@@ -113,10 +232,136 @@ describe("GrowSol Presale", () => {
     console.log("✅ admin_set_times updated");
   });
 
+  it("Rejects a stage schedule that is not increasing", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    const starts = [now + 7 * 86400, now + 3 * 86400, 0, 0].map((s) => new anchor.BN(s));
+
+    await expectRejected(
+      program.methods
+        .adminSetStageSchedule(starts, { rollForward: {} })
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  it("Rejects migrating a presale state already on the current layout", async () => {
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    expect(state.version).to.equal(1);
+
+    await expectRejected(
+      program.methods
+        .migratePresaleState()
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+          mint: mintKeypair.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  it("Rejects turning a live sale into an auction", async () => {
+    const [auctionBookPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_book")],
+      program.programId
+    );
+
+    await expectRejected(
+      program.methods
+        .configureAuction(new anchor.BN(1_000_000), new anchor.BN(100_000), new anchor.BN(1_000_000))
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+          auctionBook: auctionBookPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  let presaleEndBeforeBuy: number;
+
+  it("Owner sets an anti-sniping extension rule", async () => {
+    // any buy above 0.5 SOL in the last ~3 hours pushes the end back by a minute, twice at most
+    await program.methods
+      .adminSetExtensionRule(new anchor.BN(LAMPORTS_PER_SOL / 2), 10_000, 60, 120)
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+      })
+      .signers([owner])
+      .rpc();
+
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    presaleEndBeforeBuy = state.presaleEnd.toNumber();
+  });
+
+  it("Owner sets a per-wallet purchase cooldown", async () => {
+    await program.methods
+      .adminSetRateLimits(3_600, new anchor.BN(0))
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+      })
+      .signers([owner])
+      .rpc();
+
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    expect(state.purchaseCooldown).to.equal(3_600);
+  });
+
+  it("Owner moves stage 5 capacity into an early-bird bonus pool", async () => {
+    const pool = new anchor.BN("10000000000000000"); // 10M tokens raw
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    const stage5 = state.stages[4];
+
+    // the ladder already uses all of max_supply minus the reserve
+    const setBonus = () =>
+      program.methods
+        .adminSetBonus(
+          1_000, // +10% early bird
+          3_600, // for the first hour
+          [{ minUsdCents: new anchor.BN(500_000), bps: 500 }], // +5% from $5k
+          { perPurchase: {} },
+          pool
+        )
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+        })
+        .signers([owner])
+        .rpc();
+    await expectRejected(setBonus());
+
+    await program.methods
+      .adminSetStage(5, stage5.price, stage5.cap.sub(pool))
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+      })
+      .signers([owner])
+      .rpc();
+    await setBonus();
+
+    const updated: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    expect(updated.bonusPoolCap.toString()).to.equal(pool.toString());
+    expect(updated.earlyBirdBps).to.equal(1_000);
+    expect(updated.bonusTierCount).to.equal(1);
+  });
+
   let buyerUserAllocPda: PublicKey;
+  let buyerUserAllocBump: number;
 
   it("Buyer purchases tokens", async () => {
-    [buyerUserAllocPda] = PublicKey.findProgramAddressSync(
+    [buyerUserAllocPda, buyerUserAllocBump] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("user_alloc"),
         presaleStatePda.toBuffer(),
@@ -133,7 +378,9 @@ describe("GrowSol Presale", () => {
         treasury: treasuryPda,
         mintAuth: mintAuthPda,
         mint: mintKeypair.publicKey,
+        presaleTokenAccount: presaleTokenAta,
         userAllocation: buyerUserAllocPda,
+        denylistEntry: null,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -141,9 +388,254 @@ describe("GrowSol Presale", () => {
       .signers([buyer])
       .rpc();
 
+    // the bump is persisted on first init and verified by later instructions
+    const alloc: any = await (program.account as any).userAllocation.fetch(buyerUserAllocPda);
+    expect(alloc.bump).to.equal(buyerUserAllocBump);
+
+    // still early bird and far below the $5k tier: +10%, booked apart from the base
+    const expectedBonus = alloc.allocatedRaw.muln(1_000).divn(10_000);
+    expect(alloc.bonusRaw.toString()).to.equal(expectedBonus.toString());
+
     console.log("✅ buy_tokens executed for buyer:", buyer.publicKey.toBase58());
   });
 
+  it("Rejects repricing the current stage once the sale is live", async () => {
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    const stage1 = state.stages[0];
+
+    await expectRejected(
+      program.methods
+        .adminSetStage(1, stage1.price.addn(1), stage1.cap)
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  it("Rejects a stage cap below what the stage sold", async () => {
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    const stage1 = state.stages[0];
+
+    await expectRejected(
+      program.methods
+        .adminSetStage(1, stage1.price, stage1.sold.subn(1))
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  it("Extends the sale after a large purchase near the end", async () => {
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    expect(state.presaleEnd.toNumber()).to.equal(presaleEndBeforeBuy + 60);
+    expect(state.totalExtension).to.equal(60);
+  });
+
+  it("Rejects a second purchase inside the wallet cooldown", async () => {
+    await expectRejected(
+      program.methods
+        .buyTokens(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          buyer: buyer.publicKey,
+          presaleState: presaleStatePda,
+          treasury: treasuryPda,
+          mintAuth: mintAuthPda,
+          mint: mintKeypair.publicKey,
+          presaleTokenAccount: presaleTokenAta,
+          userAllocation: buyerUserAllocPda,
+          denylistEntry: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc()
+    );
+
+    const alloc: any = await (program.account as any).userAllocation.fetch(buyerUserAllocPda);
+    expect(alloc.lastPurchaseAt.toNumber()).to.be.greaterThan(0);
+  });
+
+  it("Rejects buy_tokens into someone else's allocation", async () => {
+    await expectRejected(
+      program.methods
+        .buyTokens(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accounts({
+          buyer: attacker.publicKey,
+          presaleState: presaleStatePda,
+          treasury: treasuryPda,
+          mintAuth: mintAuthPda,
+          mint: mintKeypair.publicKey,
+          presaleTokenAccount: presaleTokenAta,
+          userAllocation: buyerUserAllocPda,
+          denylistEntry: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([attacker])
+        .rpc()
+    );
+  });
+
+  it("Rejects purchases from a denylisted wallet", async () => {
+    const [denylistPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("denylisted"), attacker.publicKey.toBuffer()],
+      program.programId
+    );
+    const [attackerUserAllocPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("user_alloc"),
+        presaleStatePda.toBuffer(),
+        attacker.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .adminDenylistAdd(attacker.publicKey)
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+        denylistEntry: denylistPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    // with the marker passed, and with it left out while the list is non-empty
+    for (const denylistEntry of [denylistPda, null]) {
+      await expectRejected(
+        program.methods
+          .buyTokens(new anchor.BN(LAMPORTS_PER_SOL / 10))
+          .accounts({
+            buyer: attacker.publicKey,
+            presaleState: presaleStatePda,
+            treasury: treasuryPda,
+            mintAuth: mintAuthPda,
+            mint: mintKeypair.publicKey,
+            presaleTokenAccount: presaleTokenAta,
+            userAllocation: attackerUserAllocPda,
+            denylistEntry,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([attacker])
+          .rpc()
+      );
+    }
+
+    await program.methods
+      .adminDenylistRemove(attacker.publicKey)
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+        denylistEntry: denylistPda,
+      })
+      .signers([owner])
+      .rpc();
+
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    expect(state.denylistCount).to.equal(0);
+    expect(await provider.connection.getAccountInfo(denylistPda)).to.equal(null);
+  });
+
+  it("Rejects closing the allocation through a claim while the sale is live", async () => {
+    // claiming and closing mid-sale would let a buyer start over on its cooldown
+    await expectRejected(
+      program.methods
+        .claimTokens(true)
+        .accounts({
+          buyer: buyer.publicKey,
+          presaleState: presaleStatePda,
+          treasury: treasuryPda,
+          auctionBook: null,
+          mintAuth: mintAuthPda,
+          mint: mintKeypair.publicKey,
+          userAllocation: buyerUserAllocPda,
+          denylistEntry: null,
+          presaleTokenAccount: presaleTokenAta,
+          userTokenAccount: getAssociatedTokenAddressSync(
+            mintKeypair.publicKey,
+            buyer.publicKey,
+            false
+          ),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc()
+    );
+  });
+
+  it("Owner ends the sale", async () => {
+    const now = Math.floor(Date.now() / 1000);
+
+    await program.methods
+      .adminSetTimes(new anchor.BN(now - 120), new anchor.BN(now - 60))
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+      })
+      .signers([owner])
+      .rpc();
+
+    const state: any = await (program.account as any).presaleState.fetch(presaleStatePda);
+    expect(state.status).to.equal(2); // SaleStatus::Ended (stored as u8)
+  });
+
+  it("Holds claims while the buyer's allocation is frozen", async () => {
+    const setFrozen = (frozen: boolean) =>
+      program.methods
+        .adminSetAllocationFrozen(buyer.publicKey, frozen)
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+          userAllocation: buyerUserAllocPda,
+        })
+        .signers([owner])
+        .rpc();
+
+    await setFrozen(true);
+    await expectRejected(
+      program.methods
+        .claimTokens(false)
+        .accounts({
+          buyer: buyer.publicKey,
+          presaleState: presaleStatePda,
+          treasury: treasuryPda,
+          auctionBook: null,
+          mintAuth: mintAuthPda,
+          mint: mintKeypair.publicKey,
+          userAllocation: buyerUserAllocPda,
+          denylistEntry: null,
+          presaleTokenAccount: presaleTokenAta,
+          userTokenAccount: getAssociatedTokenAddressSync(
+            mintKeypair.publicKey,
+            buyer.publicKey,
+            false
+          ),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc()
+    );
+    await setFrozen(false);
+
+    const alloc: any = await (program.account as any).userAllocation.fetch(buyerUserAllocPda);
+    expect(alloc.frozen).to.equal(0);
+  });
+
   it("Buyer claims tokens", async () => {
     const buyerAta = getAssociatedTokenAddressSync(
       mintKeypair.publicKey,
@@ -152,13 +644,17 @@ describe("GrowSol Presale", () => {
     );
 
     await program.methods
-      .claimTokens()
+      .claimTokens(false)
       .accounts({
         buyer: buyer.publicKey,
         presaleState: presaleStatePda,
+        treasury: treasuryPda,
+        auctionBook: null,
         mintAuth: mintAuthPda,
         mint: mintKeypair.publicKey,
         userAllocation: buyerUserAllocPda,
+        denylistEntry: null,
+        presaleTokenAccount: presaleTokenAta,
         userTokenAccount: buyerAta,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -169,4 +665,185 @@ describe("GrowSol Presale", () => {
 
     console.log("✅ claim_tokens successful");
   });
+
+  it("Rejects claim_tokens against someone else's allocation", async () => {
+    const attackerAta = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      attacker.publicKey,
+      false
+    );
+
+    await expectRejected(
+      program.methods
+        .claimTokens(false)
+        .accounts({
+          buyer: attacker.publicKey,
+          presaleState: presaleStatePda,
+          treasury: treasuryPda,
+          auctionBook: null,
+          mintAuth: mintAuthPda,
+          mint: mintKeypair.publicKey,
+          userAllocation: buyerUserAllocPda,
+          denylistEntry: null,
+          presaleTokenAccount: presaleTokenAta,
+          userTokenAccount: attackerAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([attacker])
+        .rpc()
+    );
+  });
+
+  it("Rejects withdraw_token from a token account that is not the vault", async () => {
+    const buyerAta = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      buyer.publicKey,
+      false
+    );
+    const ownerAta = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      owner.publicKey,
+      false
+    );
+
+    await expectRejected(
+      program.methods
+        .withdrawToken(new anchor.BN(1))
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+          mintAuth: mintAuthPda,
+          mint: mintKeypair.publicKey,
+          recipient: owner.publicKey,
+          recipientTokenAccount: ownerAta,
+          presaleTokenAccount: buyerAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  it("Rejects withdraw_token with a foreign mint", async () => {
+    const foreignMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    const foreignVault = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      foreignMint,
+      presaleStatePda,
+      true
+    );
+    const ownerForeignAta = getAssociatedTokenAddressSync(
+      foreignMint,
+      owner.publicKey,
+      false
+    );
+
+    await expectRejected(
+      program.methods
+        .withdrawToken(new anchor.BN(0))
+        .accounts({
+          owner: owner.publicKey,
+          presaleState: presaleStatePda,
+          mintAuth: mintAuthPda,
+          mint: foreignMint,
+          recipient: owner.publicKey,
+          recipientTokenAccount: ownerForeignAta,
+          presaleTokenAccount: foreignVault.address,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc()
+    );
+  });
+
+  it("Owner withdraws tokens held in the presale vault", async () => {
+    const buyerAta = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      buyer.publicKey,
+      false
+    );
+    const ownerAta = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      owner.publicKey,
+      false
+    );
+
+    // seed the vault with tokens returned by the buyer
+    const amountRaw = 1_000_000_000;
+    await transfer(
+      provider.connection,
+      buyer,
+      buyerAta,
+      presaleTokenAta,
+      buyer,
+      amountRaw
+    );
+
+    await program.methods
+      .withdrawToken(new anchor.BN(amountRaw))
+      .accounts({
+        owner: owner.publicKey,
+        presaleState: presaleStatePda,
+        mintAuth: mintAuthPda,
+        mint: mintKeypair.publicKey,
+        recipient: owner.publicKey,
+        recipientTokenAccount: ownerAta,
+        presaleTokenAccount: presaleTokenAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+
+    const ownerAccount = await getAccount(provider.connection, ownerAta);
+    expect(Number(ownerAccount.amount)).to.equal(amountRaw);
+    console.log("✅ withdraw_token signed by presale_state");
+  });
+
+  it("Rejects closing someone else's allocation", async () => {
+    await expectRejected(
+      program.methods
+        .closeAllocation()
+        .accounts({
+          buyer: attacker.publicKey,
+          presaleState: presaleStatePda,
+          userAllocation: buyerUserAllocPda,
+        })
+        .signers([attacker])
+        .rpc()
+    );
+
+    const alloc: any = await (program.account as any).userAllocation.fetch(buyerUserAllocPda);
+    expect(alloc.buyer.toBase58()).to.equal(buyer.publicKey.toBase58());
+  });
+
+  it("Buyer closes a fully claimed allocation", async () => {
+    await program.methods
+      .closeAllocation()
+      .accounts({
+        buyer: buyer.publicKey,
+        presaleState: presaleStatePda,
+        userAllocation: buyerUserAllocPda,
+      })
+      .signers([buyer])
+      .rpc();
+
+    const info = await provider.connection.getAccountInfo(buyerUserAllocPda);
+    expect(info).to.equal(null);
+    console.log("✅ close_allocation returned the rent to the buyer");
+  });
 });
@@ -0,0 +1,701 @@
+// Shared setup for the bankrun suites: every `Sale` runs on its own in-process bank, so a
+// suite can initialize the singleton presale state as often as it needs and move the clock
+// freely. The validator-run flow in growsol.ts keeps covering the end-to-end happy path.
+import * as anchor from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  unpackAccount,
+  unpackMint,
+} from "@solana/spl-token";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import { expect } from "chai";
+
+import idl from "../target/idl/growsol.json";
+
+export const SOL = LAMPORTS_PER_SOL;
+/** Raw units per whole token (9 decimals). */
+export const TOKEN = 1_000_000_000n;
+
+/** `SaleStatus` as stored in the zero-copy presale state (a u8). */
+export const Status = { Pending: 0, Live: 1, Ended: 2, Finalized: 3, Cancelled: 4 } as const;
+
+export const bn = (value: number | bigint | string) => new anchor.BN(value.toString());
+
+/** Runs a transaction that must fail with the program error `name` (as named in the IDL). */
+export async function expectError(tx: Promise<unknown>, name: string) {
+  const entry = (idl as any).errors.find((e: any) => e.name === name);
+  expect(entry, `unknown program error ${name}`).to.exist;
+
+  let err: any;
+  try {
+    await tx;
+  } catch (e) {
+    err = e;
+  }
+  expect(err, `transaction should have failed with ${name}`).to.exist;
+
+  const text = `${err} ${(err.logs ?? []).join(" ")}`;
+  const matched =
+    err.code === entry.code ||
+    err.error?.errorCode?.number === entry.code ||
+    text.includes(name) ||
+    text.includes(`0x${entry.code.toString(16)}`);
+  expect(matched, `expected ${name}, got: ${text}`).to.be.true;
+}
+
+/** Runs a transaction that must fail (account constraints, token program errors, ...). */
+export async function expectRejected(tx: Promise<unknown>) {
+  let failed = false;
+  try {
+    await tx;
+  } catch (_err) {
+    failed = true;
+  }
+  expect(failed, "transaction should have been rejected").to.be.true;
+}
+
+export interface InitOptions {
+  usdPerSol?: number;
+  /** seconds relative to the bank clock */
+  startIn?: number;
+  endIn?: number;
+  maxSupply?: bigint;
+  reservedSupply?: bigint;
+  curve?: { startPrice: anchor.BN; endPrice: anchor.BN; supply: anchor.BN } | null;
+}
+
+export interface MintOptions {
+  tokenProgram?: PublicKey;
+  /** Token-2022 transfer fee; needs `tokenProgram = TOKEN_2022_PROGRAM_ID` */
+  transferFeeBps?: number;
+  maxFee?: bigint;
+  /** whole supply, minted to the owner */
+  supply?: bigint;
+}
+
+function writable(accounts: PublicKey[]) {
+  return accounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }));
+}
+
+function readonly(accounts: PublicKey[]) {
+  return accounts.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false }));
+}
+
+export class Sale {
+  readonly presaleState: PublicKey;
+  readonly treasury: PublicKey;
+  readonly mintAuth: PublicKey;
+  readonly auctionBook: PublicKey;
+  mint!: PublicKey;
+  tokenProgram: PublicKey = TOKEN_PROGRAM_ID;
+
+  private constructor(
+    readonly context: ProgramTestContext,
+    readonly provider: BankrunProvider,
+    readonly program: anchor.Program
+  ) {
+    const pda = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed)], program.programId)[0];
+    this.presaleState = pda("presale_state");
+    this.treasury = pda("treasury");
+    this.mintAuth = pda("mint_auth");
+    this.auctionBook = pda("auction_book");
+  }
+
+  /** Fresh bank with the program deployed and nothing initialized. */
+  static async start(): Promise<Sale> {
+    const context = await startAnchor(".", [], []);
+    const provider = new BankrunProvider(context);
+    const program = new anchor.Program(idl as anchor.Idl, provider);
+    return new Sale(context, provider, program);
+  }
+
+  get owner(): Keypair {
+    return this.context.payer;
+  }
+
+  get vault(): PublicKey {
+    return this.ataOf(this.presaleState);
+  }
+
+  get methods(): any {
+    return this.program.methods;
+  }
+
+  ataOf(wallet: PublicKey): PublicKey {
+    return getAssociatedTokenAddressSync(this.mint, wallet, true, this.tokenProgram);
+  }
+
+  userAllocation(buyer: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("user_alloc"), this.presaleState.toBuffer(), buyer.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  denylistEntry(wallet: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("denylisted"), wallet.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  // ---- clock and balances ----
+
+  async now(): Promise<number> {
+    return Number((await this.context.banksClient.getClock()).unixTimestamp);
+  }
+
+  /** Move the clock to `unixTimestamp` and the bank a slot forward. */
+  async warpTo(unixTimestamp: number) {
+    const clock = await this.context.banksClient.getClock();
+    this.context.warpToSlot(clock.slot + 1n);
+    this.context.setClock(
+      new Clock(
+        clock.slot + 1n,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        BigInt(unixTimestamp)
+      )
+    );
+  }
+
+  async warpBy(seconds: number) {
+    await this.warpTo((await this.now()) + seconds);
+  }
+
+  /** New system account holding `lamports`. */
+  fundedWallet(lamports = 100 * SOL): Keypair {
+    const wallet = Keypair.generate();
+    this.context.setAccount(wallet.publicKey, {
+      lamports,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    });
+    return wallet;
+  }
+
+  async lamports(account: PublicKey): Promise<number> {
+    return Number(await this.context.banksClient.getBalance(account));
+  }
+
+  async rentExempt(size: number): Promise<number> {
+    const rent = await this.context.banksClient.getRent();
+    return Number(rent.minimumBalance(BigInt(size)));
+  }
+
+  /** Treasury lamports above its rent-exempt minimum. */
+  async treasuryAboveRent(): Promise<number> {
+    return (await this.lamports(this.treasury)) - (await this.rentExempt(8));
+  }
+
+  async tokenBalance(wallet: PublicKey): Promise<bigint> {
+    const ata = this.ataOf(wallet);
+    const info = await this.context.banksClient.getAccount(ata);
+    if (!info) return 0n;
+    return unpackAccount(ata, { ...info, data: Buffer.from(info.data) }, this.tokenProgram)
+      .amount;
+  }
+
+  async mintInfo() {
+    const info = await this.context.banksClient.getAccount(this.mint);
+    return unpackMint(this.mint, { ...info!, data: Buffer.from(info!.data) }, this.tokenProgram);
+  }
+
+  async mintSupply(): Promise<bigint> {
+    return (await this.mintInfo()).supply;
+  }
+
+  async state(): Promise<any> {
+    return (this.program.account as any).presaleState.fetch(this.presaleState);
+  }
+
+  async allocation(buyer: PublicKey): Promise<any> {
+    return (this.program.account as any).userAllocation.fetchNullable(
+      this.userAllocation(buyer)
+    );
+  }
+
+  async send(instructions: TransactionInstruction[], signers: Keypair[] = []) {
+    const tx = new Transaction().add(...instructions);
+    await this.provider.sendAndConfirm(tx, signers);
+  }
+
+  /** Run a method builder and return the program events it emitted, in order. */
+  async events(builder: any, signers: Keypair[] = []): Promise<{ name: string; data: any }[]> {
+    const tx = await this.signed(builder, signers);
+    const meta = await this.context.banksClient.processTransaction(tx);
+    const parser = new anchor.EventParser(this.program.programId, this.program.coder);
+    return [...parser.parseLogs(meta.logMessages)];
+  }
+
+  /** Compute units a method builder consumes, simulated against the current bank. */
+  async computeUnits(builder: any, signers: Keypair[] = []): Promise<number> {
+    const tx = await this.signed(builder, signers);
+    const simulated = await this.context.banksClient.simulateTransaction(tx);
+    expect(simulated.result, `simulation failed: ${simulated.meta?.logMessages}`).to.equal(null);
+    return Number(simulated.meta!.computeUnitsConsumed);
+  }
+
+  private async signed(builder: any, signers: Keypair[]): Promise<Transaction> {
+    const tx: Transaction = await builder.transaction();
+    tx.recentBlockhash = (await this.context.banksClient.getLatestBlockhash())![0];
+    tx.feePayer = this.owner.publicKey;
+    tx.sign(this.owner, ...signers);
+    return tx;
+  }
+
+  // ---- setup ----
+
+  /** `initialize`: the program creates a classic SPL mint and mints on claim. */
+  async init(options: InitOptions = {}) {
+    const now = await this.now();
+    const mint = Keypair.generate();
+    this.mint = mint.publicKey;
+    this.tokenProgram = TOKEN_PROGRAM_ID;
+
+    await this.methods
+      .initialize(
+        bn(options.usdPerSol ?? 100),
+        bn(now + (options.startIn ?? -10)),
+        bn(now + (options.endIn ?? 5_000)),
+        bn(options.maxSupply ?? 1_000_000_000n * TOKEN),
+        bn(options.reservedSupply ?? 0n),
+        options.curve ?? null
+      )
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        mint: this.mint,
+        mintAuth: this.mintAuth,
+        treasury: this.treasury,
+        presaleTokenAccount: this.vault,
+        tokenProgram: this.tokenProgram,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([mint])
+      .rpc();
+  }
+
+  /** Create a mint owned by the test (optionally Token-2022 with a transfer fee) and mint
+   * `supply` to the owner. */
+  async createMint(options: MintOptions = {}): Promise<PublicKey> {
+    const tokenProgram = options.tokenProgram ?? TOKEN_PROGRAM_ID;
+    const mint = Keypair.generate();
+    const authority = this.owner.publicKey;
+    const extensions =
+      options.transferFeeBps !== undefined ? [ExtensionType.TransferFeeConfig] : [];
+    const space = getMintLen(extensions);
+
+    const instructions = [
+      SystemProgram.createAccount({
+        fromPubkey: authority,
+        newAccountPubkey: mint.publicKey,
+        space,
+        lamports: await this.rentExempt(space),
+        programId: tokenProgram,
+      }),
+    ];
+    if (options.transferFeeBps !== undefined) {
+      instructions.push(
+        createInitializeTransferFeeConfigInstruction(
+          mint.publicKey,
+          authority,
+          authority,
+          options.transferFeeBps,
+          options.maxFee ?? BigInt("18446744073709551615"),
+          tokenProgram
+        )
+      );
+    }
+    instructions.push(
+      createInitializeMintInstruction(mint.publicKey, 9, authority, null, tokenProgram)
+    );
+    await this.send(instructions, [mint]);
+
+    this.mint = mint.publicKey;
+    this.tokenProgram = tokenProgram;
+    const ownerAta = this.ataOf(authority);
+    await this.send([
+      createAssociatedTokenAccountIdempotentInstruction(
+        authority,
+        ownerAta,
+        authority,
+        this.mint,
+        tokenProgram
+      ),
+      createMintToInstruction(
+        this.mint,
+        ownerAta,
+        authority,
+        options.supply ?? 2_000_000_000n * TOKEN,
+        [],
+        tokenProgram
+      ),
+    ]);
+    return this.mint;
+  }
+
+  /** `initialize_with_mint` on the mint from `createMint` (pre-funded vault mode). */
+  async initWithMint(options: InitOptions = {}) {
+    const now = await this.now();
+    await this.methods
+      .initializeWithMint(
+        bn(options.usdPerSol ?? 100),
+        bn(now + (options.startIn ?? -10)),
+        bn(now + (options.endIn ?? 5_000)),
+        options.curve ?? null
+      )
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        mint: this.mint,
+        mintAuth: this.mintAuth,
+        treasury: this.treasury,
+        presaleTokenAccount: this.vault,
+        tokenProgram: this.tokenProgram,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  depositTokens(amountRaw: bigint) {
+    return this.depositTokensBuilder(amountRaw).rpc();
+  }
+
+  depositTokensBuilder(amountRaw: bigint) {
+    return this.methods
+      .depositTokens(bn(amountRaw))
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        mint: this.mint,
+        ownerTokenAccount: this.ataOf(this.owner.publicKey),
+        presaleTokenAccount: this.vault,
+        tokenProgram: this.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      });
+  }
+
+  // ---- owner instructions ----
+
+  setTimes(startTs: number, endTs: number) {
+    return this.methods
+      .adminSetTimes(bn(startTs), bn(endTs))
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState })
+      .rpc();
+  }
+
+  /** Close the sale by moving its end into the past. */
+  async endSale() {
+    const state = await this.state();
+    await this.setTimes(state.presaleStart.toNumber(), (await this.now()) - 1);
+  }
+
+  /** `admin_set_stage`: price in cents, cap in raw units. */
+  setStageBuilder(stage: number, priceCents: number, capRaw: bigint) {
+    return this.methods
+      .adminSetStage(stage, bn(priceCents), bn(capRaw))
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState });
+  }
+
+  insertStageBuilder(stage: number, priceCents: number, capRaw: bigint) {
+    return this.methods
+      .adminInsertStage(stage, bn(priceCents), bn(capRaw))
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState });
+  }
+
+  removeStageBuilder(stage: number) {
+    return this.methods
+      .adminRemoveStage(stage)
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState });
+  }
+
+  finalize() {
+    return this.methods
+      .finalize()
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState })
+      .rpc();
+  }
+
+  /** `finalize_mint`: pre-mint unclaimed allocations, mint the reserve and revoke the
+   * mint authority. */
+  finalizeMint(reserveRecipient: PublicKey = this.owner.publicKey) {
+    return this.methods
+      .finalizeMint()
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        mintAuth: this.mintAuth,
+        mint: this.mint,
+        presaleTokenAccount: this.vault,
+        reserveRecipient,
+        reserveTokenAccount: this.ataOf(reserveRecipient),
+        tokenProgram: this.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  cancel() {
+    return this.methods
+      .cancelSale()
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState })
+      .rpc();
+  }
+
+  /** `finalize_unsold`; `destination` is null for a burn, which takes no destination accounts. */
+  finalizeUnsold(action: object, destination: PublicKey | null) {
+    return this.methods
+      .finalizeUnsold(action)
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        mintAuth: this.mintAuth,
+        mint: this.mint,
+        presaleTokenAccount: this.vault,
+        destination,
+        destinationTokenAccount: destination && this.ataOf(destination),
+        tokenProgram: this.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  addPayoutDestination(destination: PublicKey) {
+    return this.methods
+      .addPayoutDestination(destination)
+      .accounts({ owner: this.owner.publicKey, presaleState: this.presaleState })
+      .rpc();
+  }
+
+  setBeneficiaries(
+    shares: { wallet: PublicKey; shareBps: number }[],
+    currentWallets: PublicKey[] = []
+  ) {
+    return this.methods
+      .setBeneficiaries(shares)
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        treasury: this.treasury,
+      })
+      .remainingAccounts([
+        ...readonly(shares.map((share) => share.wallet)),
+        ...writable(currentWallets),
+      ])
+      .rpc();
+  }
+
+  distribute(wallets: PublicKey[]) {
+    return this.methods
+      .distributeTreasury()
+      .accounts({
+        caller: this.owner.publicKey,
+        presaleState: this.presaleState,
+        treasury: this.treasury,
+      })
+      .remainingAccounts(writable(wallets))
+      .rpc();
+  }
+
+  /** `withdraw_sol(lamports)`, or `withdraw_all_sol` when `lamports` is null. */
+  withdrawSol(lamports: number | null, recipient: PublicKey) {
+    const method =
+      lamports === null ? this.methods.withdrawAllSol() : this.methods.withdrawSol(bn(lamports));
+    return method
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        treasury: this.treasury,
+        recipient,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  withdrawToken(amountRaw: bigint, recipient: PublicKey = this.owner.publicKey) {
+    return this.withdrawTokenBuilder(amountRaw, recipient).rpc();
+  }
+
+  withdrawTokenBuilder(amountRaw: bigint, recipient: PublicKey = this.owner.publicKey) {
+    return this.methods
+      .withdrawToken(bn(amountRaw))
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        mintAuth: this.mintAuth,
+        mint: this.mint,
+        recipient,
+        recipientTokenAccount: this.ataOf(recipient),
+        presaleTokenAccount: this.vault,
+        tokenProgram: this.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+  }
+
+  // ---- commit sales (auction / pro rata) ----
+
+  /** `configure_auction`; prices are micro-cents per whole token, `supply` whole tokens. */
+  configureAuction(floorPrice: number, tick: number, supply: number) {
+    return this.methods
+      .configureAuction(bn(floorPrice), bn(tick), bn(supply))
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        auctionBook: this.auctionBook,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  configureProRata(price: number, supply: number) {
+    return this.methods
+      .configureProRata(bn(price), bn(supply))
+      .accounts({
+        owner: this.owner.publicKey,
+        presaleState: this.presaleState,
+        auctionBook: this.auctionBook,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  commitBid(bidder: Keypair, lamports: number, limitPrice: number | null = null) {
+    return this.methods
+      .commitBid(bn(lamports), limitPrice === null ? null : bn(limitPrice))
+      .accounts({
+        bidder: bidder.publicKey,
+        presaleState: this.presaleState,
+        auctionBook: this.auctionBook,
+        treasury: this.treasury,
+        userAllocation: this.userAllocation(bidder.publicKey),
+        denylistEntry: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([bidder])
+      .rpc();
+  }
+
+  settle() {
+    return this.methods
+      .settleAuction()
+      .accounts({ presaleState: this.presaleState, auctionBook: this.auctionBook })
+      .rpc();
+  }
+
+  // ---- buyer instructions ----
+
+  buy(buyer: Keypair, lamports: number) {
+    return this.buyBuilder(buyer, lamports).signers([buyer]).rpc();
+  }
+
+  buyBuilder(buyer: Keypair, lamports: number) {
+    return this.methods
+      .buyTokens(bn(lamports))
+      .accounts({
+        buyer: buyer.publicKey,
+        presaleState: this.presaleState,
+        treasury: this.treasury,
+        mintAuth: this.mintAuth,
+        mint: this.mint,
+        presaleTokenAccount: this.vault,
+        userAllocation: this.userAllocation(buyer.publicKey),
+        denylistEntry: null,
+        tokenProgram: this.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+  }
+
+  claim(buyer: Keypair, closeWhenDone = false, auctionBook: PublicKey | null = null) {
+    return this.claimBuilder(buyer, closeWhenDone, auctionBook).signers([buyer]).rpc();
+  }
+
+  claimBuilder(buyer: Keypair, closeWhenDone = false, auctionBook: PublicKey | null = null) {
+    return this.methods
+      .claimTokens(closeWhenDone)
+      .accounts({
+        buyer: buyer.publicKey,
+        presaleState: this.presaleState,
+        treasury: this.treasury,
+        auctionBook,
+        mintAuth: this.mintAuth,
+        mint: this.mint,
+        userAllocation: this.userAllocation(buyer.publicKey),
+        denylistEntry: null,
+        presaleTokenAccount: this.vault,
+        userTokenAccount: this.ataOf(buyer.publicKey),
+        tokenProgram: this.tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+  }
+
+  refund(buyer: Keypair, auctionBook: PublicKey | null = null) {
+    return this.methods
+      .refund()
+      .accounts({
+        buyer: buyer.publicKey,
+        presaleState: this.presaleState,
+        treasury: this.treasury,
+        auctionBook,
+        userAllocation: this.userAllocation(buyer.publicKey),
+      })
+      .signers([buyer])
+      .rpc();
+  }
+
+  migrateUserAllocation(buyer: Keypair) {
+    return this.methods
+      .migrateUserAllocation()
+      .accounts({
+        buyer: buyer.publicKey,
+        presaleState: this.presaleState,
+        userAllocation: this.userAllocation(buyer.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+  }
+
+  closeAllocation(buyer: Keypair) {
+    return this.methods
+      .closeAllocation()
+      .accounts({
+        buyer: buyer.publicKey,
+        presaleState: this.presaleState,
+        userAllocation: this.userAllocation(buyer.publicKey),
+      })
+      .signers([buyer])
+      .rpc();
+  }
+}
+
+export { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID };
@@ -0,0 +1,82 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, expectError, expectRejected } from "./harness";
+import idl from "../target/idl/growsol.json";
+
+/** Baseline allocation: discriminator, buyer, allocated_raw, claimed_raw and an unset bump. */
+const BASELINE_ALLOC_LEN = 8 + 32 + 8 + 8 + 1;
+
+describe("Allocation migration", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+  let allocated: bigint;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    buyer = sale.fundedWallet();
+    await sale.buy(buyer, SOL);
+    allocated = BigInt((await sale.allocation(buyer.publicKey)).allocatedRaw.toString());
+
+    // rewrite it as the pre-versioning buy_tokens left it: no bump, nothing after it
+    const discriminator = (idl as any).accounts.find(
+      (a: any) => a.name === "UserAllocation"
+    ).discriminator;
+    const data = Buffer.alloc(BASELINE_ALLOC_LEN);
+    Buffer.from(discriminator).copy(data, 0);
+    buyer.publicKey.toBuffer().copy(data, 8);
+    data.writeBigUInt64LE(allocated, 40);
+    sale.context.setAccount(sale.userAllocation(buyer.publicKey), {
+      lamports: await sale.rentExempt(BASELINE_ALLOC_LEN),
+      data,
+      owner: sale.program.programId,
+      executable: false,
+    });
+    await sale.endSale();
+  });
+
+  it("Cannot claim a baseline allocation before it is migrated", async () => {
+    await expectRejected(sale.claim(buyer));
+  });
+
+  it("Only lets the buyer migrate the allocation", async () => {
+    const stranger = sale.fundedWallet();
+    await expectRejected(
+      sale.methods
+        .migrateUserAllocation()
+        .accounts({
+          buyer: stranger.publicKey,
+          presaleState: sale.presaleState,
+          userAllocation: sale.userAllocation(buyer.publicKey),
+        })
+        .signers([stranger])
+        .rpc()
+    );
+  });
+
+  it("Upgrades the layout and backfills the bump", async () => {
+    await sale.migrateUserAllocation(buyer);
+
+    const alloc = await sale.allocation(buyer.publicKey);
+    expect(alloc.version).to.equal(1);
+    expect(alloc.allocatedRaw.toString()).to.equal(allocated.toString());
+    expect(alloc.claimedRaw.toNumber()).to.equal(0);
+    expect(alloc.paidLamports.toNumber()).to.equal(0);
+    const [, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_alloc"), sale.presaleState.toBuffer(), buyer.publicKey.toBuffer()],
+      sale.program.programId
+    );
+    expect(alloc.bump).to.equal(bump);
+
+    await expectError(sale.migrateUserAllocation(buyer), "AlreadyMigrated");
+  });
+
+  it("Claims the migrated allocation", async () => {
+    await sale.claim(buyer);
+    expect(await sale.tokenBalance(buyer.publicKey)).to.equal(allocated);
+    expect((await sale.allocation(buyer.publicKey)).claimedRaw.toString()).to.equal(
+      allocated.toString()
+    );
+  });
+});
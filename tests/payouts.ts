@@ -0,0 +1,65 @@
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, expectError } from "./harness";
+
+describe("Payout destination allowlist", () => {
+  let sale: Sale;
+  const reserve = Keypair.generate().publicKey;
+
+  const removePayoutDestination = (destination: PublicKey) =>
+    sale.methods
+      .removePayoutDestination(destination)
+      .accounts({ owner: sale.owner.publicKey, presaleState: sale.presaleState })
+      .rpc();
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    await sale.buy(sale.fundedWallet(), SOL);
+  });
+
+  it("Rejects payouts to a wallet that is not allowlisted", async () => {
+    await expectError(sale.withdrawSol(SOL / 10, reserve), "DestinationNotAllowed");
+  });
+
+  it("Only lets the owner edit the allowlist", async () => {
+    const stranger = sale.fundedWallet();
+    await expectError(
+      sale.methods
+        .addPayoutDestination(stranger.publicKey)
+        .accounts({ owner: stranger.publicKey, presaleState: sale.presaleState })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Pays out to an allowlisted wallet", async () => {
+    await sale.addPayoutDestination(reserve);
+    await expectError(sale.addPayoutDestination(reserve), "DestinationAlreadyAllowed");
+
+    await sale.withdrawSol(SOL / 10, reserve);
+    expect(await sale.lamports(reserve)).to.equal(SOL / 10);
+    const state = await sale.state();
+    expect(state.payoutDestinationCount).to.equal(1);
+  });
+
+  it("Stops paying a wallet once it is removed", async () => {
+    await removePayoutDestination(reserve);
+    await expectError(sale.withdrawSol(SOL / 20, reserve), "DestinationNotAllowed");
+    await expectError(removePayoutDestination(reserve), "DestinationNotAllowed");
+  });
+
+  it("Caps the allowlist", async () => {
+    const state = await sale.state();
+    const room = state.payoutDestinations.length - state.payoutDestinationCount;
+    for (let i = 0; i < room; i++) {
+      await sale.addPayoutDestination(Keypair.generate().publicKey);
+    }
+    await expectError(
+      sale.addPayoutDestination(Keypair.generate().publicKey),
+      "DestinationListFull"
+    );
+  });
+});
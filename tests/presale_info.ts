@@ -0,0 +1,48 @@
+import { PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, expectRejected } from "./harness";
+
+describe("get_presale_info", () => {
+  let sale: Sale;
+
+  const info = (presaleState: PublicKey = sale.presaleState): Promise<any> =>
+    sale.methods.getPresaleInfo().accounts({ presaleState }).view();
+
+  before(async () => {
+    sale = await Sale.start();
+  });
+
+  it("Fails before the presale is initialized", async () => {
+    await expectRejected(info());
+  });
+
+  it("Returns the sale's addresses and parameters", async () => {
+    await sale.init();
+    await sale.buy(sale.fundedWallet(), SOL);
+    const state = await sale.state();
+
+    const view = await info();
+    expect(view.presaleState.toBase58()).to.equal(sale.presaleState.toBase58());
+    expect(view.owner.toBase58()).to.equal(sale.owner.publicKey.toBase58());
+    expect(view.mint.toBase58()).to.equal(sale.mint.toBase58());
+    expect(view.tokenProgram.toBase58()).to.equal(sale.tokenProgram.toBase58());
+    expect(view.mintAuth.toBase58()).to.equal(sale.mintAuth.toBase58());
+    expect(view.vault.toBase58()).to.equal(sale.vault.toBase58());
+    expect(view.treasury.toBase58()).to.equal(sale.treasury.toBase58());
+    expect(view.presaleEnd.toString()).to.equal(state.presaleEnd.toString());
+    expect(view.status).to.deep.equal({ live: {} });
+    expect(view.pricingMode).to.deep.equal({ ladder: {} });
+    expect(view.totalAllocated.toString()).to.equal(state.totalAllocated.toString());
+    expect(view.totalAllocated.gtn(0)).to.be.true;
+  });
+
+  it("Reports the effective status once the end time has passed", async () => {
+    await sale.warpTo((await sale.state()).presaleEnd.toNumber() + 1);
+    expect((await info()).status).to.deep.equal({ ended: {} });
+  });
+
+  it("Rejects an account that is not the presale state PDA", async () => {
+    await expectRejected(info(sale.treasury));
+  });
+});
@@ -0,0 +1,74 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, expectError } from "./harness";
+
+/** One US cent in micro-cents. */
+const CENT = 1_000_000;
+
+describe("Oversubscribed pro-rata sale", () => {
+  // 10k tokens at 1c is $100, i.e. 1 SOL; 4 SOL are committed
+  const SUPPLY = 10_000;
+  const COMMITS = [2 * SOL, SOL, SOL];
+  let sale: Sale;
+  let bidders: Keypair[];
+  const reserve = Keypair.generate().publicKey;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init({ startIn: 60 });
+    await sale.configureProRata(CENT, SUPPLY);
+    await sale.addPayoutDestination(reserve);
+    bidders = COMMITS.map(() => sale.fundedWallet());
+    await sale.warpTo((await sale.state()).presaleStart.toNumber() + 10);
+  });
+
+  it("Takes commits without a limit price", async () => {
+    await expectError(sale.commitBid(bidders[0], SOL, CENT), "WrongPricingMode");
+    for (const [i, bidder] of bidders.entries()) {
+      await sale.commitBid(bidder, COMMITS[i]);
+    }
+    expect((await sale.state()).refundsOwedLamports.toNumber()).to.equal(4 * SOL);
+  });
+
+  it("Keeps every commit owed until settlement", async () => {
+    await expectError(sale.withdrawSol(null, reserve), "InsufficientFunds");
+  });
+
+  it("Shares the supply pro rata at the fixed price", async () => {
+    await sale.endSale();
+    await sale.settle();
+
+    const state = await sale.state();
+    expect(state.clearingPrice.toNumber()).to.equal(CENT);
+    expect(state.marginalBucket).to.equal(0);
+    expect(state.marginalFillRaw.toString()).to.equal((BigInt(SUPPLY) * TOKEN).toString());
+    // 1 SOL is proceeds, the other 3 go back to the bidders
+    expect(state.refundsOwedLamports.toNumber()).to.equal(3 * SOL);
+  });
+
+  it("Lets the owner withdraw only the proceeds", async () => {
+    await sale.withdrawSol(null, reserve);
+    expect(await sale.lamports(reserve)).to.equal(SOL);
+    expect(await sale.treasuryAboveRent()).to.equal(3 * SOL);
+  });
+
+  it("Refunds each bidder the oversubscribed share on claim", async () => {
+    for (const [i, bidder] of bidders.entries()) {
+      // a quarter of each commit buys tokens, the rest is refunded
+      const share = (BigInt(SUPPLY) * TOKEN * BigInt(COMMITS[i])) / BigInt(4 * SOL);
+      const refund = (COMMITS[i] * 3) / 4;
+      const treasuryBefore = await sale.lamports(sale.treasury);
+      const owedBefore = (await sale.state()).refundsOwedLamports.toNumber();
+
+      await sale.claim(bidder, false, sale.auctionBook);
+
+      expect(treasuryBefore - (await sale.lamports(sale.treasury))).to.equal(refund);
+      expect(await sale.tokenBalance(bidder.publicKey)).to.equal(share);
+      expect((await sale.state()).refundsOwedLamports.toNumber()).to.equal(owedBefore - refund);
+    }
+
+    expect((await sale.state()).refundsOwedLamports.toNumber()).to.equal(0);
+    expect(await sale.treasuryAboveRent()).to.equal(0);
+  });
+});
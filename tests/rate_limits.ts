@@ -0,0 +1,56 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, bn, expectError } from "./harness";
+
+describe("Purchase cooldown", () => {
+  const COOLDOWN = 3_600;
+  let sale: Sale;
+  let buyer: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    await sale.methods
+      .adminSetRateLimits(COOLDOWN, bn(0))
+      .accounts({ owner: sale.owner.publicKey, presaleState: sale.presaleState })
+      .rpc();
+    buyer = sale.fundedWallet();
+  });
+
+  it("Rejects a second purchase inside the cooldown", async () => {
+    await sale.buy(buyer, SOL);
+    await expectError(sale.buy(buyer, SOL / 2), "PurchaseCooldown");
+  });
+
+  it("Cannot reset the cooldown by closing the allocation mid-sale", async () => {
+    await expectError(sale.claim(buyer, true), "SaleNotEnded");
+
+    // a plain claim is fine and keeps the allocation, so the cooldown stays
+    await sale.claim(buyer);
+    const alloc = await sale.allocation(buyer.publicKey);
+    expect(alloc).to.not.equal(null);
+    expect(alloc.claimedRaw.toString()).to.equal(alloc.allocatedRaw.toString());
+    expect(alloc.lastPurchaseAt.toNumber()).to.be.greaterThan(0);
+
+    await expectError(sale.closeAllocation(buyer), "SaleNotEnded");
+    await expectError(sale.buy(buyer, SOL / 3), "PurchaseCooldown");
+  });
+
+  it("Accepts the next purchase once the cooldown has passed", async () => {
+    const alloc = await sale.allocation(buyer.publicKey);
+    await sale.warpTo(alloc.lastPurchaseAt.toNumber() + COOLDOWN);
+    await sale.buy(buyer, SOL / 4);
+
+    const after = await sale.allocation(buyer.publicKey);
+    expect(after.lastPurchaseAt.toNumber()).to.equal(await sale.now());
+    expect(after.allocatedRaw.gt(alloc.allocatedRaw)).to.be.true;
+  });
+
+  it("Claims and closes the allocation once the sale has ended", async () => {
+    await sale.endSale();
+    await sale.claim(buyer, true);
+    expect(await sale.allocation(buyer.publicKey)).to.equal(null);
+    await expectError(sale.buy(buyer, SOL / 5), "SaleEnded");
+  });
+});
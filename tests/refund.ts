@@ -0,0 +1,120 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, Status, expectError, expectRejected } from "./harness";
+
+describe("Cancel and refund", () => {
+  let sale: Sale;
+  let claimer: Keypair;
+  let holder: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    claimer = sale.fundedWallet();
+    holder = sale.fundedWallet();
+    await sale.buy(claimer, SOL);
+    await sale.buy(holder, SOL / 2);
+  });
+
+  it("Only refunds a cancelled sale", async () => {
+    await expectError(sale.refund(holder), "SaleNotCancelled");
+  });
+
+  it("Only lets the owner cancel", async () => {
+    const stranger = sale.fundedWallet();
+    await expectError(
+      sale.methods
+        .cancelSale()
+        .accounts({ owner: stranger.publicKey, presaleState: sale.presaleState })
+        .signers([stranger])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
+  it("Cancels an ended sale and stops purchases and withdrawals", async () => {
+    await sale.endSale();
+    await sale.claim(claimer);
+    await sale.cancel();
+
+    expect((await sale.state()).status).to.equal(Status.Cancelled);
+    await expectError(sale.buy(sale.fundedWallet(), SOL / 4), "SaleLocked");
+    await expectError(sale.withdrawSol(null, sale.owner.publicKey), "SaleCancelled");
+    await expectError(sale.cancel(), "SaleLocked");
+  });
+
+  it("Returns exactly what the buyer paid and closes the allocation", async () => {
+    const alloc = await sale.allocation(holder.publicKey);
+    const paid = alloc.paidLamports.toNumber();
+    expect(paid).to.equal(SOL / 2);
+    const allocRent = await sale.lamports(sale.userAllocation(holder.publicKey));
+    const before = await sale.lamports(holder.publicKey);
+    const stateBefore = await sale.state();
+
+    await sale.refund(holder);
+
+    expect(await sale.lamports(holder.publicKey)).to.equal(before + paid + allocRent);
+    expect(await sale.allocation(holder.publicKey)).to.equal(null);
+    const state = await sale.state();
+    expect(state.totalRefundedLamports.toNumber()).to.equal(paid);
+    expect(state.totalAllocated.toString()).to.equal(
+      stateBefore.totalAllocated.sub(alloc.allocatedRaw).toString()
+    );
+  });
+
+  it("Refunds each allocation only once", async () => {
+    await expectRejected(sale.refund(holder));
+  });
+
+  it("Does not refund tokens that were already claimed", async () => {
+    await expectError(sale.refund(claimer), "AlreadyClaimed");
+  });
+});
+
+describe("Refunding a settled auction bid", () => {
+  // 10k tokens at the 1c floor are 1 SOL of supply for 4 SOL of bids
+  const CENT = 1_000_000;
+  const SUPPLY = 10_000;
+  let sale: Sale;
+  let claimer: Keypair;
+  let holder: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init({ startIn: 60 });
+    await sale.configureAuction(CENT, CENT, SUPPLY);
+    claimer = sale.fundedWallet();
+    holder = sale.fundedWallet();
+    await sale.warpTo((await sale.state()).presaleStart.toNumber() + 10);
+    await sale.commitBid(claimer, 2 * SOL, CENT);
+    await sale.commitBid(holder, 2 * SOL, CENT);
+    await sale.endSale();
+    await sale.settle();
+    await sale.claim(claimer, false, sale.auctionBook);
+    await sale.cancel();
+  });
+
+  it("Needs the auction book to take back an unclaimed fill", async () => {
+    await expectRejected(sale.refund(holder));
+  });
+
+  it("Returns the whole commit and takes its fill out of the totals", async () => {
+    const claimed = await sale.allocation(claimer.publicKey);
+    const before = await sale.state();
+    // the two bids share the supply evenly; the claimed half is already in the allocation
+    expect(before.totalAllocated.toString()).to.equal(claimed.allocatedRaw.muln(2).toString());
+    const allocRent = await sale.lamports(sale.userAllocation(holder.publicKey));
+    const holderBefore = await sale.lamports(holder.publicKey);
+
+    await sale.refund(holder, sale.auctionBook);
+
+    expect(await sale.lamports(holder.publicKey)).to.equal(holderBefore + 2 * SOL + allocRent);
+    const state = await sale.state();
+    expect(state.totalAllocated.toString()).to.equal(claimed.allocatedRaw.toString());
+    expect(state.refundsOwedLamports.toNumber()).to.equal(0);
+    expect(state.totalRefundedLamports.toNumber()).to.equal(2 * SOL);
+    // only what the claimed bid paid for is left
+    expect(await sale.treasuryAboveRent()).to.equal(claimed.paidLamports.toNumber());
+  });
+});
@@ -0,0 +1,111 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, expectError } from "./harness";
+
+describe("Admin stage editor", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+
+  /** Apply a stage edit and return the StageUpdated event it emitted. */
+  async function edit(builder: any) {
+    const events = await sale.events(builder);
+    const updated = events.find((e) => e.name === "StageUpdated");
+    expect(updated, "StageUpdated should be emitted").to.exist;
+    return updated!.data;
+  }
+
+  async function ladder(): Promise<{ price: number; cap: bigint; sold: bigint }[]> {
+    const state = await sale.state();
+    return state.stages.slice(0, state.stageCount).map((s: any) => ({
+      price: s.price.toNumber(),
+      cap: BigInt(s.cap.toString()),
+      sold: BigInt(s.sold.toString()),
+    }));
+  }
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init({ startIn: 60 });
+    buyer = sale.fundedWallet();
+  });
+
+  it("Edits any stage before the sale starts", async () => {
+    const set = await edit(sale.setStageBuilder(1, 2, 100n * TOKEN));
+    expect(set.edit).to.deep.equal({ set: {} });
+    expect(set.stage).to.equal(1);
+    expect(set.price.toNumber()).to.equal(2);
+    expect(set.cap.toString()).to.equal((100n * TOKEN).toString());
+    expect(set.stageCount).to.equal(5);
+
+    // a new first stage goes in ahead of the one the sale would open with
+    const inserted = await edit(sale.insertStageBuilder(1, 1, 100n * TOKEN));
+    expect(inserted.edit).to.deep.equal({ insert: {} });
+    expect(inserted.stage).to.equal(1);
+    expect(inserted.stageCount).to.equal(6);
+
+    const removed = await edit(sale.removeStageBuilder(6));
+    expect(removed.edit).to.deep.equal({ remove: {} });
+    expect(removed.stage).to.equal(6);
+    expect(removed.price.toNumber()).to.equal(5);
+    expect(removed.stageCount).to.equal(5);
+
+    const stages = await ladder();
+    expect(stages.map((s) => s.price)).to.deep.equal([1, 2, 2, 3, 4]);
+    expect(stages[0].cap).to.equal(100n * TOKEN);
+    expect(stages[1].cap).to.equal(100n * TOKEN);
+  });
+
+  it("Moves on to stage 2 once stage 1 sells out", async () => {
+    await sale.warpTo((await sale.state()).presaleStart.toNumber() + 10);
+    // $2: 100 tokens at 1c sell out stage 1, the other $1 buys 50 tokens at 2c
+    await sale.buy(buyer, SOL / 50);
+
+    const stages = await ladder();
+    expect((await sale.state()).currentStage).to.equal(2);
+    expect(stages[0].sold).to.equal(100n * TOKEN);
+    expect(stages[1].sold).to.equal(50n * TOKEN);
+  });
+
+  it("Rejects edits to a sold-out stage", async () => {
+    await expectError(sale.setStageBuilder(1, 1, 200n * TOKEN).rpc(), "StageLocked");
+    await expectError(sale.removeStageBuilder(1).rpc(), "StageLocked");
+    await expectError(sale.insertStageBuilder(2, 1, 10n * TOKEN).rpc(), "StageLocked");
+  });
+
+  it("Rejects repricing or removing the current stage", async () => {
+    await expectError(sale.setStageBuilder(2, 3, 100n * TOKEN).rpc(), "StageLocked");
+    await expectError(sale.removeStageBuilder(2).rpc(), "StageLocked");
+    await expectError(sale.setStageBuilder(2, 2, 40n * TOKEN).rpc(), "StageCapBelowSold");
+  });
+
+  it("Lets the current stage's cap move and edits stages after it", async () => {
+    const set = await edit(sale.setStageBuilder(2, 2, 80n * TOKEN));
+    expect(set.edit).to.deep.equal({ set: {} });
+    expect(set.cap.toString()).to.equal((80n * TOKEN).toString());
+
+    const repriced = await edit(sale.setStageBuilder(3, 3, 1_000n * TOKEN));
+    expect(repriced.stage).to.equal(3);
+    expect(repriced.price.toNumber()).to.equal(3);
+
+    const inserted = await edit(sale.insertStageBuilder(3, 2, 500n * TOKEN));
+    expect(inserted.edit).to.deep.equal({ insert: {} });
+    expect(inserted.stageCount).to.equal(6);
+
+    const removed = await edit(sale.removeStageBuilder(3));
+    expect(removed.edit).to.deep.equal({ remove: {} });
+    expect(removed.cap.toString()).to.equal((500n * TOKEN).toString());
+    expect(removed.stageCount).to.equal(5);
+
+    const stages = await ladder();
+    expect((await sale.state()).currentStage).to.equal(2);
+    expect(stages.map((s) => s.price)).to.deep.equal([1, 2, 3, 3, 4]);
+    expect(stages.map((s) => s.cap)).to.deep.equal([
+      100n * TOKEN,
+      80n * TOKEN,
+      1_000n * TOKEN,
+      200_000_000n * TOKEN,
+      225_000_000n * TOKEN,
+    ]);
+  });
+});
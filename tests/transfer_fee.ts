@@ -0,0 +1,78 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, TOKEN_2022_PROGRAM_ID, expectError } from "./harness";
+
+// the default ladder sells 1B tokens
+const SALE_SUPPLY = 1_000_000_000n * TOKEN;
+const FEE_BPS = 100n;
+
+/** Token-2022 rounds the transfer fee up. */
+const feeOn = (amount: bigint) => (amount * FEE_BPS + 9_999n) / 10_000n;
+
+describe("Token-2022 transfer fee mint", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.createMint({
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      transferFeeBps: Number(FEE_BPS),
+      supply: 2n * SALE_SUPPLY,
+    });
+    await sale.initWithMint();
+    buyer = sale.fundedWallet();
+  });
+
+  it("Counts deposits net of the fee and reports the fee", async () => {
+    const [deposited] = await sale.events(sale.depositTokensBuilder(SALE_SUPPLY));
+
+    const fee = feeOn(SALE_SUPPLY);
+    expect(deposited.name).to.equal("TokensDeposited");
+    expect(deposited.data.feeRaw.toString()).to.equal(fee.toString());
+    expect(deposited.data.amountRaw.toString()).to.equal((SALE_SUPPLY - fee).toString());
+    expect(await sale.tokenBalance(sale.presaleState)).to.equal(SALE_SUPPLY - fee);
+    const state = await sale.state();
+    expect(state.totalDeposited.toString()).to.equal((SALE_SUPPLY - fee).toString());
+  });
+
+  it("Does not sell until the net deposits cover the inventory", async () => {
+    await expectError(sale.buy(buyer, SOL), "InsufficientInventory");
+
+    const topUp = SALE_SUPPLY / 50n;
+    await sale.depositTokens(topUp);
+    const state = await sale.state();
+    const net = SALE_SUPPLY - feeOn(SALE_SUPPLY) + topUp - feeOn(topUp);
+    expect(state.totalDeposited.toString()).to.equal(net.toString());
+    await sale.buy(buyer, SOL);
+  });
+
+  it("Pays withdrawals net of the fee", async () => {
+    const surplus = 1_000n * TOKEN;
+    const recipient = Keypair.generate().publicKey;
+    await sale.addPayoutDestination(recipient);
+
+    const [withdrawn] = await sale.events(sale.withdrawTokenBuilder(surplus, recipient));
+
+    expect(withdrawn.name).to.equal("WithdrawnToken");
+    expect(withdrawn.data.amountRaw.toString()).to.equal(surplus.toString());
+    expect(withdrawn.data.feeRaw.toString()).to.equal(feeOn(surplus).toString());
+    expect(await sale.tokenBalance(recipient)).to.equal(surplus - feeOn(surplus));
+  });
+
+  it("Books the whole claim and reports what the fee withheld", async () => {
+    await sale.endSale();
+    const alloc = await sale.allocation(buyer.publicKey);
+    const owed = BigInt(alloc.allocatedRaw.add(alloc.bonusRaw).toString());
+
+    const events = await sale.events(sale.claimBuilder(buyer), [buyer]);
+    const claimed = events.find((e) => e.name === "TokensClaimed")!;
+
+    expect(claimed.data.claimedRaw.toString()).to.equal(owed.toString());
+    expect(claimed.data.feeRaw.toString()).to.equal(feeOn(owed).toString());
+    expect(await sale.tokenBalance(buyer.publicKey)).to.equal(owed - feeOn(owed));
+    const after = await sale.allocation(buyer.publicKey);
+    expect(after.claimedRaw.toString()).to.equal(owed.toString());
+  });
+});
@@ -0,0 +1,203 @@
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, expectError } from "./harness";
+
+describe("Treasury revenue split", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+  const alice = Keypair.generate().publicKey;
+  const bob = Keypair.generate().publicKey;
+  const carol = Keypair.generate().publicKey;
+
+  const share = (proceeds: number, bps: number) => Math.floor((proceeds * bps) / 10_000);
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    buyer = sale.fundedWallet();
+  });
+
+  it("Rejects a split that does not cover 100%", async () => {
+    await expectError(
+      sale.setBeneficiaries([
+        { wallet: alice, shareBps: 6_000 },
+        { wallet: bob, shareBps: 3_000 },
+      ]),
+      "InvalidBeneficiaryShares"
+    );
+  });
+
+  it("Pays each beneficiary its share of the proceeds", async () => {
+    await sale.setBeneficiaries([
+      { wallet: alice, shareBps: 6_000 },
+      { wallet: bob, shareBps: 4_000 },
+    ]);
+    await sale.buy(buyer, SOL);
+
+    const proceeds = await sale.treasuryAboveRent();
+    await sale.distribute([alice, bob]);
+
+    expect(await sale.lamports(alice)).to.equal(share(proceeds, 6_000));
+    expect(await sale.lamports(bob)).to.equal(share(proceeds, 4_000));
+    const state = await sale.state();
+    expect(state.totalDistributed.toNumber()).to.equal(
+      share(proceeds, 6_000) + share(proceeds, 4_000)
+    );
+  });
+
+  it("Rejects a distribution to wallets outside the split", async () => {
+    await sale.buy(buyer, SOL / 2);
+    await expectError(sale.distribute([alice, carol]), "BeneficiaryMismatch");
+    await expectError(sale.distribute([alice]), "BeneficiaryMismatch");
+  });
+
+  it("Rejects owner withdrawals while a split is configured", async () => {
+    await expectError(sale.withdrawSol(SOL / 10, sale.owner.publicKey), "RevenueSplitActive");
+    await expectError(sale.withdrawSol(null, sale.owner.publicKey), "RevenueSplitActive");
+  });
+
+  it("Requires pending proceeds to settle under the old split before it changes", async () => {
+    await expectError(
+      sale.setBeneficiaries([{ wallet: carol, shareBps: 10_000 }]),
+      "BeneficiaryMismatch"
+    );
+  });
+
+  it("Settles pending proceeds under the old split when the split changes", async () => {
+    const aliceBefore = await sale.lamports(alice);
+    const bobBefore = await sale.lamports(bob);
+    const pending = await sale.treasuryAboveRent();
+
+    await sale.setBeneficiaries([{ wallet: carol, shareBps: 10_000 }], [alice, bob]);
+
+    expect((await sale.lamports(alice)) - aliceBefore).to.equal(share(pending, 6_000));
+    expect((await sale.lamports(bob)) - bobBefore).to.equal(share(pending, 4_000));
+
+    // the new beneficiary only shares in what comes in afterwards
+    await sale.buy(buyer, SOL / 4);
+    const proceeds = await sale.treasuryAboveRent();
+    await sale.distribute([carol]);
+    expect(await sale.lamports(carol)).to.equal(proceeds);
+
+    const state = await sale.state();
+    expect(state.beneficiaries[0].distributed.toNumber()).to.equal(proceeds);
+  });
+
+  it("Lets the owner withdraw again once the split is removed", async () => {
+    await sale.buy(buyer, SOL / 8);
+    await sale.setBeneficiaries([], [carol]);
+
+    const recipientBefore = await sale.lamports(sale.owner.publicKey);
+    expect(await sale.treasuryAboveRent()).to.equal(0);
+    await sale.buy(buyer, SOL / 16);
+    const proceeds = await sale.treasuryAboveRent();
+    await sale.withdrawSol(null, sale.owner.publicKey);
+    expect(await sale.treasuryAboveRent()).to.equal(0);
+    expect(await sale.lamports(sale.owner.publicKey)).to.be.greaterThan(
+      recipientBefore + proceeds - SOL / 100
+    );
+  });
+});
+
+describe("Treasury split with a wallet that cannot be paid", () => {
+  let sale: Sale;
+  let buyer: Keypair;
+  const alice = Keypair.generate().publicKey;
+  const dave = Keypair.generate().publicKey;
+
+  const share = (proceeds: number, bps: number) => Math.floor((proceeds * bps) / 10_000);
+
+  /** Give `wallet` exactly its rent-exempt minimum, optionally as a program account. */
+  async function fund(wallet: PublicKey, executable = false) {
+    sale.context.setAccount(wallet, {
+      lamports: await sale.rentExempt(0),
+      data: Buffer.alloc(0),
+      owner: executable
+        ? new PublicKey("BPFLoader2111111111111111111111111111111111")
+        : SystemProgram.programId,
+      executable,
+    });
+  }
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    buyer = sale.fundedWallet();
+  });
+
+  it("Rejects beneficiary wallets that cannot take lamports", async () => {
+    for (const wallet of [PublicKey.default, sale.program.programId, sale.treasury]) {
+      await expectError(
+        sale.setBeneficiaries([{ wallet, shareBps: 10_000 }]),
+        "InvalidBeneficiaryWallet"
+      );
+    }
+  });
+
+  it("Holds back a share too small to make an empty wallet rent exempt", async () => {
+    await sale.setBeneficiaries([
+      { wallet: alice, shareBps: 9_999 },
+      { wallet: dave, shareBps: 1 },
+    ]);
+    await sale.buy(buyer, SOL / 8);
+
+    const proceeds = await sale.treasuryAboveRent();
+    await sale.distribute([alice, dave]);
+
+    expect(await sale.lamports(alice)).to.equal(share(proceeds, 9_999));
+    expect(await sale.lamports(dave)).to.equal(0);
+    const state = await sale.state();
+    expect(state.beneficiaries[1].owed.toNumber()).to.equal(share(proceeds, 1));
+    expect(state.beneficiaries[1].distributed.toNumber()).to.equal(0);
+    expect(state.totalDistributed.toNumber()).to.equal(share(proceeds, 9_999));
+  });
+
+  it("Pays what was held back once the wallet can take it", async () => {
+    await fund(dave);
+    await sale.buy(buyer, SOL / 4);
+
+    const owed = (await sale.state()).beneficiaries[1].owed.toNumber();
+    const proceeds = (await sale.treasuryAboveRent()) - owed;
+    const aliceBefore = await sale.lamports(alice);
+    const daveBefore = await sale.lamports(dave);
+    await sale.distribute([alice, dave]);
+
+    expect((await sale.lamports(alice)) - aliceBefore).to.equal(share(proceeds, 9_999));
+    expect((await sale.lamports(dave)) - daveBefore).to.equal(share(proceeds, 1) + owed);
+    const state = await sale.state();
+    expect(state.beneficiaries[1].owed.toNumber()).to.equal(0);
+    expect(state.beneficiaries[1].distributed.toNumber()).to.equal(share(proceeds, 1) + owed);
+  });
+
+  it("Lets the owner drop a program wallet from the split", async () => {
+    await fund(dave, true);
+    await sale.buy(buyer, SOL / 16);
+    const proceeds = await sale.treasuryAboveRent();
+    const aliceBefore = await sale.lamports(alice);
+
+    // settling under the old split holds dave's share back instead of failing
+    await sale.setBeneficiaries([{ wallet: alice, shareBps: 10_000 }], [alice, dave]);
+    expect((await sale.lamports(alice)) - aliceBefore).to.equal(share(proceeds, 9_999));
+    expect(await sale.lamports(dave)).to.equal(await sale.rentExempt(0));
+
+    // what dave was owed went back to the proceeds, now all alice's
+    const state = await sale.state();
+    expect(state.beneficiaryCount).to.equal(1);
+    expect(state.beneficiaries[0].owed.toNumber()).to.equal(0);
+    const released = await sale.treasuryAboveRent();
+    expect(released).to.equal(proceeds - share(proceeds, 9_999));
+    await sale.distribute([alice]);
+    expect((await sale.lamports(alice)) - aliceBefore).to.equal(proceeds);
+  });
+
+  it("Lets the owner withdraw once the split is removed", async () => {
+    await sale.buy(buyer, SOL / 32);
+    await sale.setBeneficiaries([], [alice]);
+    await sale.buy(buyer, SOL / 64);
+
+    const proceeds = await sale.treasuryAboveRent();
+    await sale.withdrawSol(proceeds, sale.owner.publicKey);
+    expect(await sale.treasuryAboveRent()).to.equal(0);
+  });
+});
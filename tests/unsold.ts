@@ -0,0 +1,77 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, TOKEN, expectError, expectRejected } from "./harness";
+
+// the default ladder sells 1B tokens
+const SALE_SUPPLY = 1_000_000_000n * TOKEN;
+const BURN = { burn: {} };
+const RESERVE = { sendToReserve: {} };
+
+/** Run a sale with one purchase through to `finalize`. */
+async function finalizedSale(preFunded: boolean): Promise<[Sale, Keypair]> {
+  const sale = await Sale.start();
+  if (preFunded) {
+    await sale.createMint();
+    await sale.initWithMint();
+    await sale.depositTokens(SALE_SUPPLY);
+  } else {
+    await sale.init();
+  }
+  const buyer = sale.fundedWallet();
+  await sale.buy(buyer, SOL);
+  await sale.endSale();
+  await sale.finalize();
+  return [sale, buyer];
+}
+
+describe("Unsold inventory", () => {
+  it("Burns never-sold capacity off the hard cap without a destination", async () => {
+    const [sale] = await finalizedSale(false);
+    const before = await sale.state();
+
+    await sale.finalizeUnsold(BURN, null);
+
+    const state = await sale.state();
+    const unsold = SALE_SUPPLY - BigInt(before.totalAllocated.toString());
+    expect(state.unsoldAmount.toString()).to.equal(unsold.toString());
+    expect(BigInt(state.maxSupply.toString())).to.equal(
+      BigInt(before.maxSupply.toString()) - unsold
+    );
+    await expectError(sale.finalizeUnsold(BURN, null), "UnsoldAlreadyFinalized");
+  });
+
+  it("Burns unsold vault inventory and leaves what backs allocations", async () => {
+    const [sale, buyer] = await finalizedSale(true);
+    const supplyBefore = await sale.mintSupply();
+    const alloc = await sale.allocation(buyer.publicKey);
+    const owed = BigInt(alloc.allocatedRaw.add(alloc.bonusRaw).toString());
+
+    await sale.finalizeUnsold(BURN, null);
+
+    expect(await sale.tokenBalance(sale.presaleState)).to.equal(owed);
+    expect(await sale.mintSupply()).to.equal(supplyBefore - (SALE_SUPPLY - owed));
+
+    await sale.claim(buyer);
+    expect(await sale.tokenBalance(buyer.publicKey)).to.equal(owed);
+  });
+
+  it("Sends unsold vault inventory to an allowlisted reserve", async () => {
+    const [sale, buyer] = await finalizedSale(true);
+    const reserve = Keypair.generate().publicKey;
+    const alloc = await sale.allocation(buyer.publicKey);
+    const owed = BigInt(alloc.allocatedRaw.add(alloc.bonusRaw).toString());
+
+    // a transfer needs the destination and its token account
+    await expectRejected(sale.finalizeUnsold(RESERVE, null));
+    await expectError(sale.finalizeUnsold(RESERVE, reserve), "DestinationNotAllowed");
+
+    await sale.addPayoutDestination(reserve);
+    await sale.finalizeUnsold(RESERVE, reserve);
+
+    expect(await sale.tokenBalance(reserve)).to.equal(SALE_SUPPLY - owed);
+    expect(await sale.tokenBalance(sale.presaleState)).to.equal(owed);
+    const state = await sale.state();
+    expect(state.unsoldDestination.toBase58()).to.equal(reserve.toBase58());
+  });
+});
@@ -0,0 +1,35 @@
+import { Keypair } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { SOL, Sale, expectError } from "./harness";
+
+describe("Treasury withdrawals", () => {
+  let sale: Sale;
+  const reserve = Keypair.generate().publicKey;
+
+  before(async () => {
+    sale = await Sale.start();
+    await sale.init();
+    await sale.addPayoutDestination(reserve);
+  });
+
+  it("Has nothing to withdraw before the first purchase", async () => {
+    expect(await sale.treasuryAboveRent()).to.equal(0);
+    await expectError(sale.withdrawSol(null, reserve), "InsufficientFunds");
+  });
+
+  it("Will not take the treasury below its rent-exempt minimum", async () => {
+    await sale.buy(sale.fundedWallet(), SOL);
+    const proceeds = await sale.treasuryAboveRent();
+    await expectError(sale.withdrawSol(proceeds + 1, reserve), "TreasuryRentExemption");
+  });
+
+  it("Withdraws every lamport above rent and leaves the treasury rent exempt", async () => {
+    const proceeds = await sale.treasuryAboveRent();
+    await sale.withdrawSol(null, reserve);
+
+    expect(await sale.lamports(reserve)).to.equal(proceeds);
+    expect(await sale.lamports(sale.treasury)).to.equal(await sale.rentExempt(8));
+    await expectError(sale.withdrawSol(null, reserve), "InsufficientFunds");
+  });
+});